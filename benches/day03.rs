@@ -0,0 +1,114 @@
+//! Compares the bitset solvers in `day03` with the `BTreeSet` approach they
+//! replaced, on the real input and on larger generated ones.
+
+use std::collections::BTreeSet;
+
+use aoc_2022::day03::{parse_input, solve1, solve2};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn priority(item: u8) -> i64 {
+    match item {
+        b'a'..=b'z' => (item - b'a' + 1) as i64,
+        _ => (item - b'A' + 27) as i64,
+    }
+}
+
+fn btree_solve1(lines: &[&str]) -> i64 {
+    lines
+        .iter()
+        .map(|line| {
+            let (c1, c2) = line.as_bytes().split_at(line.len() / 2);
+            let c1: BTreeSet<u8> = c1.iter().copied().collect();
+            let c2: BTreeSet<u8> = c2.iter().copied().collect();
+            c1.intersection(&c2)
+                .map(|&item| priority(item))
+                .sum::<i64>()
+        })
+        .sum()
+}
+
+fn btree_solve2(lines: &[&str]) -> i64 {
+    lines
+        .chunks_exact(3)
+        .map(|group| {
+            let mut common: BTreeSet<u8> = group[0].bytes().collect();
+            for line in group {
+                let items = line.bytes().collect();
+                common = common.intersection(&items).copied().collect();
+            }
+            common.into_iter().map(priority).sum::<i64>()
+        })
+        .sum()
+}
+
+/// `groups` groups of three valid rucksacks: each rucksack draws from its own
+/// third of the alphabet, plus the group's badge.
+fn generate(groups: usize, seed: u64) -> String {
+    let mut state = seed;
+    let mut next = move |n: usize| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((state >> 33) % n as u64) as usize
+    };
+    let mut out = String::new();
+    for _ in 0..groups {
+        let badge = LETTERS[next(52)];
+        let others: Vec<u8> = LETTERS.iter().copied().filter(|&c| c != badge).collect();
+        for pool in others.chunks(17) {
+            let (left, right) = pool.split_at(8);
+            let shared = right[next(right.len())];
+            let half = 8 + next(16);
+            let mut c1 = vec![shared, badge];
+            let mut c2 = vec![shared];
+            while c1.len() < half {
+                c1.push(left[next(left.len())]);
+            }
+            while c2.len() < half {
+                c2.push(right[next(right.len())]);
+            }
+            out.extend(c1.into_iter().chain(c2).map(char::from));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn bench(c: &mut Criterion) {
+    let real = std::fs::read_to_string("data/day03.input").expect("day03 input");
+    let inputs = [
+        ("real", real),
+        ("generated-30k", generate(10_000, 1)),
+        ("generated-300k", generate(100_000, 2)),
+    ];
+    let mut group = c.benchmark_group("day03");
+    for (name, raw) in &inputs {
+        let rucksacks = parse_input(raw).expect("valid input");
+        let lines: Vec<&str> = raw.lines().collect();
+        assert_eq!(solve1::<i64>(&rucksacks).unwrap(), btree_solve1(&lines));
+        assert_eq!(solve2::<i64>(&rucksacks).unwrap(), btree_solve2(&lines));
+
+        group.bench_with_input(BenchmarkId::new("btree", name), &lines, |b, lines| {
+            b.iter(|| {
+                (
+                    btree_solve1(black_box(lines)),
+                    btree_solve2(black_box(lines)),
+                )
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bitset", name), &rucksacks, |b, r| {
+            b.iter(|| {
+                (
+                    solve1::<i64>(black_box(r)).unwrap(),
+                    solve2::<i64>(black_box(r)).unwrap(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,115 @@
+use std::{
+    io::Read,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail};
+use aoc_2022::{lookup, Day, DAYS};
+use clap::Parser;
+use serde::Serialize;
+
+/// Solve Advent of Code 2022 puzzles.
+#[derive(Parser)]
+struct Args {
+    /// Which day to solve.
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    day: Option<u32>,
+    /// Only solve this part (1 or 2). Both parts are solved by default.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Input file, or `-` for stdin. Defaults to `data/dayNN.input`.
+    #[arg(short, long, conflicts_with = "all")]
+    input: Option<PathBuf>,
+    /// Solve every registered day against its default input.
+    #[arg(long)]
+    all: bool,
+    /// Print results as JSON.
+    #[arg(long)]
+    json: bool,
+    /// Report how long parsing and solving took.
+    #[arg(long)]
+    time: bool,
+}
+
+#[derive(Serialize)]
+struct Answer {
+    day: u32,
+    part: u8,
+    answer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_us: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solve_us: Option<u128>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let days: Vec<&Day> = match args.day {
+        Some(day) => vec![lookup(day).ok_or_else(|| anyhow!("day {} is not solved yet", day))?],
+        None => DAYS.iter().collect(),
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    let mut answers = Vec::new();
+    for day in days {
+        let raw = read_input(day.day, args.input.as_ref())?;
+        let start = Instant::now();
+        let prepared = (day.prepare)(&raw)?;
+        let parse_time = start.elapsed();
+        for &part in &parts {
+            let start = Instant::now();
+            let answer = match part {
+                1 => prepared.part1()?,
+                2 => prepared.part2()?,
+                _ => bail!("invalid part: {}", part),
+            };
+            let solve_time = start.elapsed();
+            answers.push(Answer {
+                day: day.day,
+                part,
+                answer,
+                parse_us: args.time.then_some(parse_time.as_micros()),
+                solve_us: args.time.then_some(solve_time.as_micros()),
+            });
+        }
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&answers)?);
+    } else {
+        for a in &answers {
+            match (a.parse_us, a.solve_us) {
+                (Some(parse), Some(solve)) => println!(
+                    "day {:02} part {}: {} (parse {:?}, solve {:?})",
+                    a.day,
+                    a.part,
+                    a.answer,
+                    Duration::from_micros(parse as u64),
+                    Duration::from_micros(solve as u64),
+                ),
+                _ => println!("day {:02} part {}: {}", a.day, a.part, a.answer),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_input(day: u32, path: Option<&PathBuf>) -> anyhow::Result<String> {
+    match path {
+        Some(path) if path.as_os_str() == "-" => {
+            let mut raw = String::new();
+            std::io::stdin().read_to_string(&mut raw)?;
+            Ok(raw)
+        }
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("could not read {}: {}", path.display(), e)),
+        None => {
+            let path = format!("data/day{:02}.input", day);
+            std::fs::read_to_string(&path).map_err(|e| anyhow!("could not read {}: {}", path, e))
+        }
+    }
+}
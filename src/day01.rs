@@ -80,30 +80,231 @@ Find the top three Elves carrying the most Calories. How many Calories are those
 Elves carrying in total?
 */
 
-pub fn parse_input(input: &str) -> anyhow::Result<Vec<Vec<i32>>> {
-    let mut acc = Vec::new();
-    let mut cur = Vec::new();
-    for line in input.trim().lines().map(|l| l.trim()) {
-        if line.is_empty() {
-            acc.push(cur);
-            cur = Vec::new();
-        } else {
-            cur.push(line.parse()?);
+pub mod report;
+
+use std::{cmp::Reverse, collections::BinaryHeap, io::BufRead};
+
+use anyhow::anyhow;
+use nom::{
+    character::complete::{digit1, line_ending, multispace0, space0},
+    combinator::{all_consuming, cut, map_res},
+    error::{context, ContextError, ErrorKind, ParseError as _, VerboseError},
+    multi::{many0_count, separated_list1},
+    sequence::{delimited, terminated},
+    Finish,
+};
+
+use crate::{
+    num::{checked_sum, Total},
+    parse::{ParseError, ParseResult},
+};
+
+/// The food items carried by a single elf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    pub items: Vec<u64>,
+}
+
+impl Elf {
+    pub fn total<T: Total + TryFrom<u64>>(&self) -> anyhow::Result<T> {
+        let items = self
+            .items
+            .iter()
+            .map(|&item| widen(item))
+            .collect::<anyhow::Result<Vec<T>>>()?;
+        Ok(checked_sum(items)?)
+    }
+}
+
+/// What to do when elves are separated by more than one blank line, which
+/// would otherwise describe an elf carrying nothing at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyGroups {
+    #[default]
+    Skip,
+    Reject,
+}
+
+pub fn parse_input(raw: &str) -> anyhow::Result<Vec<Elf>> {
+    parse_elves(raw, EmptyGroups::default())
+}
+
+pub fn parse_elves(raw: &str, policy: EmptyGroups) -> anyhow::Result<Vec<Elf>> {
+    let parser = |input| elves_parser(input, policy);
+    match all_consuming(delimited(multispace0, parser, multispace0))(raw).finish() {
+        Ok((_, elves)) => Ok(elves),
+        Err(e) => Err(ParseError::from_nom(1, raw, e).into()),
+    }
+}
+fn elves_parser(input: &str, policy: EmptyGroups) -> ParseResult<'_, Vec<Elf>> {
+    let (mut input, first) = elf_parser(input)?;
+    let mut elves = vec![first];
+    loop {
+        // Elves are separated by a line break followed by a blank line; any
+        // further blank lines describe elves that carry nothing.
+        let (extra, _) = match terminated(line_ending, blank_line_parser)(input) {
+            Ok(v) => v,
+            Err(nom::Err::Error(_)) => break,
+            Err(e) => return Err(e),
+        };
+        let (rest, extra_blanks) = many0_count(blank_line_parser)(extra)?;
+        // Only trailing whitespace may follow a separator without another
+        // elf; anything else is a malformed elf, not the end of the list.
+        if rest.trim_start_matches([' ', '\t', '\r', '\n']).is_empty() {
+            break;
+        }
+        let (rest, elf) = cut(elf_parser)(rest)?;
+        if extra_blanks > 0 && policy == EmptyGroups::Reject {
+            return Err(nom::Err::Failure(VerboseError::add_context(
+                extra,
+                "calorie count (empty elves are not allowed)",
+                VerboseError::from_error_kind(extra, ErrorKind::Verify),
+            )));
         }
+        input = rest;
+        elves.push(elf);
     }
-    Ok(acc)
+    Ok((input, elves))
+}
+fn blank_line_parser(input: &str) -> ParseResult<'_, &str> {
+    terminated(space0, line_ending)(input)
+}
+fn elf_parser(input: &str) -> ParseResult<'_, Elf> {
+    let (input, items) = separated_list1(line_ending, item_parser)(input)?;
+    Ok((input, Elf { items }))
+}
+fn item_parser(input: &str) -> ParseResult<'_, u64> {
+    delimited(
+        space0,
+        context("calorie count", map_res(digit1, str::parse)),
+        space0,
+    )(input)
 }
 
-pub fn find_max_sum(xs: Vec<Vec<i32>>) -> anyhow::Result<i32> {
-    xs.iter()
-        .map(|ys| ys.iter().sum())
+pub fn find_max_sum<T: Total + TryFrom<u64>>(elves: &[Elf]) -> anyhow::Result<T> {
+    let totals = elves
+        .iter()
+        .map(Elf::total)
+        .collect::<anyhow::Result<Vec<T>>>()?;
+    totals
+        .into_iter()
         .max()
-        .ok_or_else(|| anyhow::anyhow!("empty input"))
+        .ok_or_else(|| anyhow!("empty input"))
+}
+
+pub fn find_top_k_sum<T: Total + TryFrom<u64>>(elves: &[Elf], k: usize) -> anyhow::Result<T> {
+    let mut totals = elves
+        .iter()
+        .map(Elf::total)
+        .collect::<anyhow::Result<Vec<T>>>()?;
+    totals.sort_by_key(|&t| Reverse(t));
+    Ok(checked_sum(totals.into_iter().take(k))?)
+}
+
+fn widen<T: Total + TryFrom<u64>>(item: u64) -> anyhow::Result<T> {
+    T::try_from(item).map_err(|_| {
+        anyhow!(
+            "item {} does not fit in {}",
+            item,
+            std::any::type_name::<T>()
+        )
+    })
 }
 
-pub fn find_top_k_sum(mut xs: Vec<Vec<i32>>, k: usize) -> anyhow::Result<i32> {
-    xs.sort_by_cached_key(|ys| std::cmp::Reverse(ys.iter().sum::<i32>()));
-    Ok(xs.iter().take(k).map(|ys| ys.iter().sum::<i32>()).sum())
+/// The `k` elves carrying the most calories, identified by their 0-based
+/// position in the input and ordered from most to fewest calories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopK<T> {
+    pub total: T,
+    pub elves: Vec<(usize, T)>,
+}
+
+/// Like `find_top_k_sum`, but reads elves one line at a time and only ever
+/// holds the best `k` of them in memory. Ties are broken in favour of the
+/// elf that appears first.
+pub fn stream_top_k<T: Total + TryFrom<u64>>(
+    mut reader: impl BufRead,
+    k: usize,
+) -> anyhow::Result<TopK<T>> {
+    // A min-heap of the best elves seen so far; among equal totals the
+    // later elf sorts lower, so it is the first to be evicted. It grows as
+    // elves are read rather than up front, since k may far exceed their
+    // number.
+    let mut heap: BinaryHeap<Reverse<(T, Reverse<usize>)>> = BinaryHeap::new();
+    let mut offer = |elf: usize, total: T| {
+        heap.push(Reverse((total, Reverse(elf))));
+        if heap.len() > k {
+            heap.pop();
+        }
+    };
+
+    let mut elf = 0;
+    let mut current: Option<T> = None;
+    let mut line = String::new();
+    let mut line_no = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_no += 1;
+        let text = line.trim_end_matches(['\r', '\n']);
+        if text.trim().is_empty() {
+            if let Some(total) = current.take() {
+                offer(elf, total);
+                elf += 1;
+            }
+            continue;
+        }
+        // The same parser as parse_input, so both report errors alike.
+        let item = match all_consuming(item_parser)(text).finish() {
+            Ok((_, item)) => item,
+            Err(e) => {
+                let err = ParseError::from_nom(1, text, e);
+                return Err(ParseError {
+                    line: line_no,
+                    ..err
+                }
+                .into());
+            }
+        };
+        let item = widen(item)?;
+        current = Some(match current {
+            None => item,
+            Some(total) => checked_sum([total, item])?,
+        });
+    }
+    if let Some(total) = current {
+        offer(elf, total);
+    }
+
+    let mut elves: Vec<(usize, T)> = heap
+        .into_iter()
+        .map(|Reverse((total, Reverse(elf)))| (elf, total))
+        .collect();
+    elves.sort_by_key(|&(elf, total)| (Reverse(total), elf));
+    Ok(TopK {
+        total: checked_sum(elves.iter().map(|&(_, total)| total))?,
+        elves,
+    })
+}
+
+pub struct Day01;
+
+impl crate::Solution for Day01 {
+    type Input = Vec<Elf>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(raw: &str) -> anyhow::Result<Self::Input> {
+        parse_input(raw)
+    }
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        find_max_sum(input)
+    }
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        find_top_k_sum(input, 3)
+    }
 }
 
 #[cfg(test)]
@@ -129,7 +330,100 @@ mod test {
             10000
         "#;
         let input = parse_input(input)?;
-        assert_eq!(find_max_sum(input)?, 24_000);
+        assert_eq!(find_max_sum::<i64>(&input)?, 24_000);
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_group_test() -> anyhow::Result<()> {
+        let elves = parse_input("1\n2\n\n3\n\n4\n5")?;
+        assert_eq!(elves.len(), 3);
+        assert_eq!(elves[2].items, vec![4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn blank_lines_test() -> anyhow::Result<()> {
+        let input = "1\r\n2\r\n\r\n  \r\n\r\n3\r\n\r\n";
+        let elves = parse_input(input)?;
+        assert_eq!(
+            elves,
+            vec![Elf { items: vec![1, 2] }, Elf { items: vec![3] }]
+        );
+
+        let err = parse_elves(input, EmptyGroups::Reject).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (4, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_item_test() {
+        let err = parse_input("1\n2\n\n3x\n").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.day, err.line, err.column), (1, 4, 2));
+    }
+
+    #[test]
+    fn malformed_group_test() {
+        let err = parse_input("1\n\nx").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.expected, "calorie count");
+    }
+
+    #[test]
+    fn overflow_test() -> anyhow::Result<()> {
+        let input = format!("{}\n{}\n\n1", i32::MAX, i32::MAX);
+        let input = parse_input(&input)?;
+        assert!(find_max_sum::<i32>(&input).is_err());
+        assert_eq!(find_max_sum::<i64>(&input)?, 2 * i32::MAX as i64);
+        assert_eq!(find_top_k_sum::<u128>(&input, 2)?, 2 * i32::MAX as u128 + 1);
+
+        // Items themselves may be wider than i32.
+        let big = i32::MAX as u64 + 1;
+        let input = parse_input(&format!("{}\n1\n\n{}", big, u64::MAX))?;
+        assert_eq!(input[0].items, vec![big, 1]);
+        assert!(find_max_sum::<i64>(&input).is_err());
+        assert_eq!(find_max_sum::<u64>(&input)?, u64::MAX);
+        assert_eq!(
+            find_top_k_sum::<u128>(&input, 2)?,
+            u64::MAX as u128 + big as u128 + 1
+        );
+        let top = stream_top_k::<u128>(format!("{}\n1", big).as_bytes(), 1)?;
+        assert_eq!(top.total, big as u128 + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn stream_top_k_test() -> anyhow::Result<()> {
+        let input = "1000\n2000\n3000\n\n4000\n\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n\n6000";
+        let top = stream_top_k::<i32>(input.as_bytes(), 3)?;
+        assert_eq!(top.total, 45_000);
+        assert_eq!(top.elves, vec![(3, 24_000), (2, 11_000), (4, 10_000)]);
+
+        // The first elf and the last elf tie, so the first one wins.
+        let top = stream_top_k::<u64>(input.as_bytes(), 5)?;
+        assert_eq!(top.elves[3..], [(0, 6_000), (5, 6_000)]);
+        assert_eq!(stream_top_k::<u64>(input.as_bytes(), 0)?.elves, vec![]);
+        let everyone = stream_top_k::<u64>(input.as_bytes(), usize::MAX)?;
+        assert_eq!((everyone.elves.len(), everyone.total), (6, 61_000));
+
+        let err = stream_top_k::<i64>("1\n\n-2\n".as_bytes(), 1).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.snippet.as_str()), (3, "-2"));
+
+        // Both entry points agree on where the error is and what was expected.
+        for input in ["1\n\n   12x\n", "1\n\n  x\n", "1\n\n99999999999999999999\n"] {
+            let streamed = stream_top_k::<i64>(input.as_bytes(), 1).unwrap_err();
+            let parsed = parse_input(input).unwrap_err();
+            assert_eq!(
+                streamed.downcast_ref::<ParseError>(),
+                parsed.downcast_ref::<ParseError>()
+            );
+        }
+        let err = stream_top_k::<i64>("   12x".as_bytes(), 1).unwrap_err();
+        assert_eq!(err.downcast_ref::<ParseError>().unwrap().column, 6);
         Ok(())
     }
 
@@ -137,7 +431,7 @@ mod test {
     fn part1() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day01.input")?;
         let input = parse_input(&input)?;
-        assert_eq!(find_max_sum(input)?, 71023);
+        assert_eq!(find_max_sum::<i64>(&input)?, 71023);
         Ok(())
     }
 
@@ -145,7 +439,10 @@ mod test {
     fn part2() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day01.input")?;
         let input = parse_input(&input)?;
-        assert_eq!(find_top_k_sum(input, 3)?, 206289);
+        assert_eq!(find_top_k_sum::<u64>(&input, 3)?, 206289);
+
+        let raw = std::fs::read("data/day01.input")?;
+        assert_eq!(stream_top_k::<u64>(&raw[..], 3)?.total, 206289);
         Ok(())
     }
 }
@@ -0,0 +1,286 @@
+use std::fmt;
+
+use anyhow::{anyhow, bail};
+use serde::Serialize;
+
+use super::Elf;
+
+/// How a single elf compares to the rest of the expedition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ElfSummary {
+    /// 0-based position of the elf in the input.
+    pub index: usize,
+    pub items: usize,
+    pub total: i64,
+    /// 1-based rank by total; elves with equal totals share a rank and the
+    /// following rank is skipped ("1224" ranking).
+    pub rank: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Percentile {
+    pub p: u8,
+    pub value: f64,
+}
+
+/// An inclusive range of totals and how many elves fall within it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    pub lo: i64,
+    pub hi: i64,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Statistics {
+    pub count: usize,
+    pub min: i64,
+    pub max: i64,
+    pub mean: f64,
+    pub median: f64,
+    /// Population standard deviation of the totals.
+    pub std_dev: f64,
+    pub percentiles: Vec<Percentile>,
+    pub histogram: Vec<Bucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    /// Every elf, ordered by rank and then by position in the input.
+    pub elves: Vec<ElfSummary>,
+    pub stats: Statistics,
+}
+
+const PERCENTILES: [u8; 6] = [10, 25, 50, 75, 90, 99];
+
+/// The most histogram buckets a report will allocate.
+pub const MAX_BUCKETS: usize = 10_000;
+
+/// Summarizes every elf's load, grouping totals into `buckets` equally wide
+/// histogram buckets.
+pub fn report(elves: &[Elf], buckets: usize) -> anyhow::Result<Report> {
+    if buckets == 0 {
+        bail!("histogram needs at least one bucket");
+    }
+    if buckets > MAX_BUCKETS {
+        bail!(
+            "histogram can have at most {} buckets, got {}",
+            MAX_BUCKETS,
+            buckets
+        );
+    }
+    let buckets = i64::try_from(buckets)?;
+    let mut summaries = elves
+        .iter()
+        .enumerate()
+        .map(|(index, elf)| {
+            Ok(ElfSummary {
+                index,
+                items: elf.items.len(),
+                total: elf.total()?,
+                rank: 0,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    summaries.sort_by_key(|s| (std::cmp::Reverse(s.total), s.index));
+    for i in 0..summaries.len() {
+        summaries[i].rank = match i {
+            0 => 1,
+            _ if summaries[i].total == summaries[i - 1].total => summaries[i - 1].rank,
+            _ => i + 1,
+        };
+    }
+
+    let mut totals: Vec<i64> = summaries.iter().map(|s| s.total).collect();
+    totals.sort_unstable();
+    let (&min, &max) = totals
+        .first()
+        .zip(totals.last())
+        .ok_or_else(|| anyhow!("empty input"))?;
+    let count = totals.len();
+    let mean = totals.iter().map(|&t| t as f64).sum::<f64>() / count as f64;
+    let variance = totals
+        .iter()
+        .map(|&t| (t as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count as f64;
+
+    // ceil((max - min + 1) / buckets), so the buckets always reach max; the
+    // last ones may lie past it and stay empty, saturating near i64::MAX.
+    let width = ((max - min) / buckets) + 1;
+    let mut histogram: Vec<Bucket> = (0..buckets)
+        .map(|b| Bucket {
+            lo: min.saturating_add(b.saturating_mul(width)),
+            hi: min.saturating_add((b + 1).saturating_mul(width) - 1),
+            count: 0,
+        })
+        .collect();
+    for &t in &totals {
+        histogram[((t - min) / width) as usize].count += 1;
+    }
+
+    Ok(Report {
+        elves: summaries,
+        stats: Statistics {
+            count,
+            min,
+            max,
+            mean,
+            median: percentile(&totals, 50.0),
+            std_dev: variance.sqrt(),
+            percentiles: PERCENTILES
+                .iter()
+                .map(|&p| Percentile {
+                    p,
+                    value: percentile(&totals, p as f64),
+                })
+                .collect(),
+            histogram,
+        },
+    })
+}
+
+/// The `p`th percentile of a non-empty, sorted slice, linearly interpolating
+/// between the two closest ranks.
+fn percentile(sorted: &[i64], p: f64) -> f64 {
+    let pos = (sorted.len() - 1) as f64 * p.clamp(0.0, 100.0) / 100.0;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    let frac = pos - lo as f64;
+    sorted[lo] as f64 * (1.0 - frac) + sorted[hi] as f64 * frac
+}
+
+impl Report {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>5} {:>5} {:>5} {:>10}",
+            "rank", "elf", "items", "total"
+        )?;
+        for e in &self.elves {
+            writeln!(
+                f,
+                "{:>5} {:>5} {:>5} {:>10}",
+                e.rank, e.index, e.items, e.total
+            )?;
+        }
+        let s = &self.stats;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "count {}, min {}, max {}, mean {:.1}, median {:.1}, std dev {:.1}",
+            s.count, s.min, s.max, s.mean, s.median, s.std_dev
+        )?;
+        let percentiles: Vec<String> = s
+            .percentiles
+            .iter()
+            .map(|p| format!("p{} {:.1}", p.p, p.value))
+            .collect();
+        writeln!(f, "{}", percentiles.join(", "))?;
+        for b in &s.histogram {
+            writeln!(
+                f,
+                "{:>10}-{:<10} {:>5} {}",
+                b.lo,
+                b.hi,
+                b.count,
+                "#".repeat(b.count.min(60))
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day01::parse_input;
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        let input = parse_input(
+            "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n\n11000",
+        )?;
+        let report = report(&input, 3)?;
+        let ranks: Vec<(usize, i64, usize)> = report
+            .elves
+            .iter()
+            .map(|e| (e.index, e.total, e.rank))
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![
+                (3, 24000, 1),
+                (2, 11000, 2),
+                (5, 11000, 2),
+                (4, 10000, 4),
+                (0, 6000, 5),
+                (1, 4000, 6),
+            ]
+        );
+
+        let stats = &report.stats;
+        assert_eq!((stats.count, stats.min, stats.max), (6, 4000, 24000));
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10500.0);
+        assert!((stats.std_dev - 6377.04).abs() < 0.01);
+        assert_eq!(
+            stats.histogram,
+            vec![
+                Bucket {
+                    lo: 4000,
+                    hi: 10666,
+                    count: 3
+                },
+                Bucket {
+                    lo: 10667,
+                    hi: 17333,
+                    count: 2
+                },
+                Bucket {
+                    lo: 17334,
+                    hi: 24000,
+                    count: 1
+                },
+            ]
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()?)?;
+        assert_eq!(json["elves"][0]["total"], 24000);
+        assert!(report
+            .to_string()
+            .starts_with(" rank   elf items      total\n    1     3     3      24000\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn percentile_test() {
+        let xs = [10, 20, 30, 40];
+        assert_eq!(percentile(&xs, 0.0), 10.0);
+        assert_eq!(percentile(&xs, 50.0), 25.0);
+        assert_eq!(percentile(&xs, 100.0), 40.0);
+        assert_eq!(percentile(&[7], 90.0), 7.0);
+    }
+
+    #[test]
+    fn histogram_test() -> anyhow::Result<()> {
+        let input = parse_input("0\n\n1\n\n2\n\n4")?;
+        let counts: Vec<(i64, i64, usize)> = report(&input, 4)?
+            .stats
+            .histogram
+            .iter()
+            .map(|b| (b.lo, b.hi, b.count))
+            .collect();
+        assert_eq!(counts, vec![(0, 1, 2), (2, 3, 1), (4, 5, 1), (6, 7, 0)]);
+
+        assert!(report(&input, MAX_BUCKETS).is_ok());
+        assert!(report(&input, MAX_BUCKETS + 1).is_err());
+        assert!(report(&input, usize::MAX).is_err());
+        Ok(())
+    }
+}
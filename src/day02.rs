@@ -71,110 +71,95 @@ Following the Elf's instructions for the second column, what would your total
 score be if everything goes exactly according to your strategy guide?
 */
 
-use anyhow::anyhow;
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::multispace1,
-    combinator::{all_consuming, value},
-    multi::separated_list1,
-    sequence::separated_pair,
-    IResult,
-};
-
-#[derive(Debug, Clone, Copy)]
+pub mod cipher;
+pub mod counter;
+pub mod equilibrium;
+pub mod evaluate;
+pub mod game;
+pub mod infer;
+pub mod strategy;
+pub mod tournament;
+
+use std::fmt;
+
+use crate::num::Total;
+use cipher::Cipher;
+use game::{cyclic_outcome, Game};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Shape {
     Rock,
     Paper,
     Scissors,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl Shape {
+    /// Every shape, ordered so that each one beats the one before it.
+    pub const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    /// This shape's position in `Shape::ALL`, and its weapon number in
+    /// `Game::rock_paper_scissors`.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Outcome {
     Win,
     Loss,
     Draw,
 }
 
-pub fn parse_input1(raw: &str) -> anyhow::Result<Vec<(Shape, Shape)>> {
-    match all_consuming(separated_list1(multispace1, shapes_parser))(raw.trim()) {
-        Ok((_, lines)) => Ok(lines),
-        Err(e) => Err(anyhow!("could not parse input [{}]: {}", e, raw)),
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
     }
 }
-pub fn parse_input2(raw: &str) -> anyhow::Result<Vec<(Shape, Outcome)>> {
-    match all_consuming(separated_list1(multispace1, shape_outcome_parser))(raw.trim()) {
-        Ok((_, lines)) => Ok(lines),
-        Err(e) => Err(anyhow!("could not parse input [{}]: {}", e, raw)),
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
     }
 }
-fn shape_outcome_parser(input: &str) -> IResult<&str, (Shape, Outcome)> {
-    let (input, (p1, p2)) = separated_pair(shape_parser, multispace1, outcome_parser)(input)?;
-    Ok((input, (p1, p2)))
-}
-fn shapes_parser(input: &str) -> IResult<&str, (Shape, Shape)> {
-    let (input, (p1, p2)) = separated_pair(shape_parser, multispace1, shape_parser)(input)?;
-    Ok((input, (p1, p2)))
-}
-fn shape_parser(input: &str) -> IResult<&str, Shape> {
-    alt((
-        value(Shape::Rock, tag("A")),
-        value(Shape::Paper, tag("B")),
-        value(Shape::Scissors, tag("C")),
-        value(Shape::Rock, tag("X")),
-        value(Shape::Paper, tag("Y")),
-        value(Shape::Scissors, tag("Z")),
-    ))(input)
-}
 
-fn outcome_parser(input: &str) -> IResult<&str, Outcome> {
-    alt((
-        value(Outcome::Loss, tag("X")),
-        value(Outcome::Draw, tag("Y")),
-        value(Outcome::Win, tag("Z")),
-    ))(input)
+pub fn parse_input1(raw: &str) -> anyhow::Result<Vec<(Shape, Shape)>> {
+    Cipher::shapes().parse_guide(raw)
+}
+pub fn parse_input2(raw: &str) -> anyhow::Result<Vec<(Shape, Outcome)>> {
+    Cipher::outcomes().parse_guide(raw)
 }
 
-pub fn score1(input: &[(Shape, Shape)]) -> i32 {
-    input
-        .iter()
-        .map(|&(theirs, mine)| score_shape(mine) + score_outcome(decide_outcome(theirs, mine)))
-        .sum()
+pub fn score1<T: Total>(input: &[(Shape, Shape)]) -> anyhow::Result<T> {
+    Game::rock_paper_scissors().score1(
+        input
+            .iter()
+            .map(|&(theirs, mine)| (theirs.index(), mine.index())),
+    )
 }
 
-pub fn score2(input: &[(Shape, Outcome)]) -> i32 {
-    input
-        .iter()
-        .map(|&(theirs, outcome)| {
-            score_shape(decide_shape(theirs, outcome)) + score_outcome(outcome)
-        })
-        .sum()
+pub fn score2<T: Total>(input: &[(Shape, Outcome)]) -> anyhow::Result<T> {
+    Game::rock_paper_scissors().score2(
+        input
+            .iter()
+            .map(|&(theirs, outcome)| (theirs.index(), outcome)),
+    )
 }
 
-fn decide_outcome(theirs: Shape, mine: Shape) -> Outcome {
-    match (theirs, mine) {
-        (Shape::Rock, Shape::Rock)
-        | (Shape::Paper, Shape::Paper)
-        | (Shape::Scissors, Shape::Scissors) => Outcome::Draw,
-        (Shape::Rock, Shape::Paper)
-        | (Shape::Paper, Shape::Scissors)
-        | (Shape::Scissors, Shape::Rock) => Outcome::Win,
-        (Shape::Rock, Shape::Scissors)
-        | (Shape::Paper, Shape::Rock)
-        | (Shape::Scissors, Shape::Paper) => Outcome::Loss,
-    }
+pub fn decide_outcome(theirs: Shape, mine: Shape) -> Outcome {
+    cyclic_outcome(Shape::ALL.len(), theirs.index(), mine.index())
 }
 
-fn decide_shape(theirs: Shape, outcome: Outcome) -> Shape {
-    match (theirs, outcome) {
-        (_, Outcome::Draw) => theirs,
-        (Shape::Rock, Outcome::Win) | (Shape::Scissors, Outcome::Loss) => Shape::Paper,
-        (Shape::Rock, Outcome::Loss) | (Shape::Paper, Outcome::Win) => Shape::Scissors,
-        (Shape::Paper, Outcome::Loss) | (Shape::Scissors, Outcome::Win) => Shape::Rock,
-    }
+pub fn decide_shape(theirs: Shape, outcome: Outcome) -> Shape {
+    let offset = match outcome {
+        Outcome::Draw => 0,
+        Outcome::Win => 1,
+        Outcome::Loss => 2,
+    };
+    Shape::ALL[(theirs.index() + offset) % Shape::ALL.len()]
 }
 
-fn score_shape(shape: Shape) -> i32 {
+fn score_shape(shape: Shape) -> u8 {
     match shape {
         Shape::Rock => 1,
         Shape::Paper => 2,
@@ -182,7 +167,7 @@ fn score_shape(shape: Shape) -> i32 {
     }
 }
 
-fn score_outcome(outcome: Outcome) -> i32 {
+fn score_outcome(outcome: Outcome) -> u8 {
     match outcome {
         Outcome::Win => 6,
         Outcome::Loss => 0,
@@ -190,6 +175,24 @@ fn score_outcome(outcome: Outcome) -> i32 {
     }
 }
 
+pub struct Day02;
+
+impl crate::Solution for Day02 {
+    type Input = (Vec<(Shape, Shape)>, Vec<(Shape, Outcome)>);
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(raw: &str) -> anyhow::Result<Self::Input> {
+        Ok((parse_input1(raw)?, parse_input2(raw)?))
+    }
+    fn part1((input, _): &Self::Input) -> anyhow::Result<Self::Answer1> {
+        score1(input)
+    }
+    fn part2((_, input): &Self::Input) -> anyhow::Result<Self::Answer2> {
+        score2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -202,7 +205,7 @@ mod test {
             C Z
         "#;
         let input = parse_input1(input)?;
-        assert_eq!(score1(&input), 15);
+        assert_eq!(score1::<i32>(&input)?, 15);
         Ok(())
     }
 
@@ -210,7 +213,7 @@ mod test {
     fn part1() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day02.input")?;
         let input = parse_input1(&input)?;
-        assert_eq!(score1(&input), 15337);
+        assert_eq!(score1::<u64>(&input)?, 15337);
         Ok(())
     }
 
@@ -218,7 +221,7 @@ mod test {
     fn part2() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day02.input")?;
         let input = parse_input2(&input)?;
-        assert_eq!(score2(&input), 11696);
+        assert_eq!(score2::<i64>(&input)?, 11696);
         Ok(())
     }
 }
@@ -0,0 +1,269 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Display},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, ensure};
+use nom::{
+    branch::alt,
+    character::complete::{alpha1, anychar, char, multispace0, space0, space1},
+    combinator::{all_consuming, map_opt, map_res, value, verify},
+    error::context,
+    multi::separated_list1,
+    sequence::{delimited, separated_pair},
+    Finish,
+};
+
+use super::{Outcome, Shape};
+use crate::parse::{parse_lines, ParseError, ParseResult};
+
+/// Something the second column of a strategy guide can decode to.
+pub trait Symbol: Copy + Debug + Display + FromStr<Err = anyhow::Error> {}
+
+impl Symbol for Shape {}
+impl Symbol for Outcome {}
+
+/// How the symbols in each column of a strategy guide should be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cipher<T> {
+    opponent: BTreeMap<char, Shape>,
+    response: BTreeMap<char, T>,
+}
+
+impl<T: Symbol> Cipher<T> {
+    pub fn new(
+        opponent: impl IntoIterator<Item = (char, Shape)>,
+        response: impl IntoIterator<Item = (char, T)>,
+    ) -> anyhow::Result<Self> {
+        Ok(Cipher {
+            opponent: column("opponent", opponent)?,
+            response: column("response", response)?,
+        })
+    }
+
+    pub fn opponent(&self) -> &BTreeMap<char, Shape> {
+        &self.opponent
+    }
+
+    pub fn response(&self) -> &BTreeMap<char, T> {
+        &self.response
+    }
+
+    /// Decodes a strategy guide, one round per line.
+    pub fn parse_guide(&self, raw: &str) -> anyhow::Result<Vec<(Shape, T)>> {
+        Ok(parse_lines(2, raw, |input| self.round_parser(input))?)
+    }
+
+    fn round_parser<'a>(&self, input: &'a str) -> ParseResult<'a, (Shape, T)> {
+        separated_pair(
+            context(
+                "opponent symbol",
+                map_opt(anychar, |c| self.opponent.get(&c).copied()),
+            ),
+            space1,
+            context(
+                "response symbol",
+                map_opt(anychar, |c| self.response.get(&c).copied()),
+            ),
+        )(input)
+    }
+}
+
+fn column<T>(
+    name: &str,
+    entries: impl IntoIterator<Item = (char, T)>,
+) -> anyhow::Result<BTreeMap<char, T>> {
+    let mut acc = BTreeMap::new();
+    for (symbol, value) in entries {
+        ensure!(
+            !symbol.is_whitespace(),
+            "{} symbols cannot be whitespace",
+            name
+        );
+        ensure!(
+            acc.insert(symbol, value).is_none(),
+            "{} symbol {:?} is mapped more than once",
+            name,
+            symbol
+        );
+    }
+    ensure!(!acc.is_empty(), "{} column has no symbols", name);
+    Ok(acc)
+}
+
+const OPPONENT: [(char, Shape); 3] = [
+    ('A', Shape::Rock),
+    ('B', Shape::Paper),
+    ('C', Shape::Scissors),
+];
+
+impl Cipher<Shape> {
+    /// The reading from part 1: X, Y and Z are the shapes to play.
+    pub fn shapes() -> Self {
+        Cipher::new(
+            OPPONENT,
+            [
+                ('X', Shape::Rock),
+                ('Y', Shape::Paper),
+                ('Z', Shape::Scissors),
+            ],
+        )
+        .expect("the part 1 cipher is valid")
+    }
+}
+
+impl Cipher<Outcome> {
+    /// The reading from part 2: X, Y and Z are how the round should end.
+    pub fn outcomes() -> Self {
+        Cipher::new(
+            OPPONENT,
+            [
+                ('X', Outcome::Loss),
+                ('Y', Outcome::Draw),
+                ('Z', Outcome::Win),
+            ],
+        )
+        .expect("the part 2 cipher is valid")
+    }
+}
+
+/// Reads a cipher written as `A=Rock B=Paper C=Scissors; X=Loss Y=Draw Z=Win`:
+/// the opponent's column, a semicolon, then the response column. Entries may
+/// be separated by spaces or commas.
+impl<T: Symbol> FromStr for Cipher<T> {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> anyhow::Result<Self> {
+        let (_, (opponent, response)) = all_consuming(delimited(
+            multispace0,
+            separated_pair(
+                entries_parser::<Shape>,
+                delimited(multispace0, char(';'), multispace0),
+                entries_parser::<T>,
+            ),
+            multispace0,
+        ))(raw)
+        .finish()
+        .map_err(|e| ParseError::from_nom(2, raw, e))?;
+        Cipher::new(opponent, response)
+    }
+}
+
+fn entries_parser<T: Symbol>(input: &str) -> ParseResult<'_, Vec<(char, T)>> {
+    let separator = alt((
+        value((), delimited(space0, char(','), space0)),
+        value((), space1),
+    ));
+    separated_list1(separator, entry_parser)(input)
+}
+fn entry_parser<T: Symbol>(input: &str) -> ParseResult<'_, (char, T)> {
+    separated_pair(
+        context(
+            "symbol",
+            verify(anychar, |&c| !c.is_whitespace() && !"=,;".contains(c)),
+        ),
+        delimited(space0, char('='), space0),
+        context("shape or outcome name", map_res(alpha1, str::parse)),
+    )(input)
+}
+
+impl<T: Symbol> Display for Cipher<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opponent: Vec<String> = self
+            .opponent
+            .iter()
+            .map(|(c, s)| format!("{}={}", c, s))
+            .collect();
+        let response: Vec<String> = self
+            .response
+            .iter()
+            .map(|(c, s)| format!("{}={}", c, s))
+            .collect();
+        write!(f, "{}; {}", opponent.join(" "), response.join(" "))
+    }
+}
+
+impl FromStr for Shape {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Shape::ALL
+            .into_iter()
+            .find(|shape| shape.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow!("unknown shape: {}", s))
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        [Outcome::Win, Outcome::Loss, Outcome::Draw]
+            .into_iter()
+            .find(|outcome| outcome.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow!("unknown outcome: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day02::{score1, score2};
+
+    #[test]
+    fn standard_ciphers_test() -> anyhow::Result<()> {
+        let shapes: Cipher<Shape> =
+            "A=Rock B=Paper C=Scissors; X=Rock Y=Paper Z=Scissors".parse()?;
+        assert_eq!(shapes, Cipher::shapes());
+        let outcomes: Cipher<Outcome> = "A=rock,B=paper,C=scissors;X=loss,Y=draw,Z=win".parse()?;
+        assert_eq!(outcomes, Cipher::outcomes());
+        assert_eq!(
+            Cipher::outcomes().to_string(),
+            "A=Rock B=Paper C=Scissors; X=Loss Y=Draw Z=Win"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn custom_cipher_test() -> anyhow::Result<()> {
+        let shapes: Cipher<Shape> =
+            "r=Rock p=Paper s=Scissors; 1=Paper 2=Rock 3=Scissors".parse()?;
+        let guide = shapes.parse_guide("r 1\np 2\ns 3")?;
+        assert_eq!(
+            guide,
+            vec![
+                (Shape::Rock, Shape::Paper),
+                (Shape::Paper, Shape::Rock),
+                (Shape::Scissors, Shape::Scissors)
+            ]
+        );
+        assert_eq!(score1::<i32>(&guide)?, 15);
+
+        let outcomes = Cipher::new(OPPONENT, [('L', Outcome::Loss), ('W', Outcome::Win)])?;
+        let guide = outcomes.parse_guide("A W\nB L")?;
+        assert_eq!(score2::<i32>(&guide)?, 8 + 1);
+        let err = outcomes.parse_guide("A W\nB D").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "response symbol");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_cipher_test() {
+        let err = "A=Rock A=Paper; X=Win"
+            .parse::<Cipher<Outcome>>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "opponent symbol 'A' is mapped more than once"
+        );
+        let err = "A=Rock; X=Lizard".parse::<Cipher<Shape>>().unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (11, "shape or outcome name")
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::ensure;
+
+use super::{decide_shape, score_outcome, score_shape, Outcome, Shape};
+
+/// Limits on how often we can win without looking suspicious.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Constraints {
+    /// Never win more than this many rounds in a row.
+    pub max_win_streak: Option<usize>,
+    /// Keep the fraction of rounds won strictly below this.
+    pub max_win_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterStrategy {
+    pub responses: Vec<Shape>,
+    pub wins: usize,
+    pub score: i64,
+}
+
+/// The highest scoring sequence of responses to `opponent` that respects
+/// `constraints`.
+///
+/// This is a dynamic program over (round, current win streak, wins so far),
+/// so it takes O(n * streak * wins) time. Only two rounds of scores are kept
+/// at once, plus one bit per state recording whether to win, so it needs
+/// O(streak * wins) words and O(n * streak * wins) bits; dimensions that are
+/// not constrained are not tracked.
+pub fn counter_strategy(
+    opponent: &[Shape],
+    constraints: Constraints,
+) -> anyhow::Result<CounterStrategy> {
+    let n = opponent.len();
+    if n == 0 {
+        return Ok(CounterStrategy {
+            responses: Vec::new(),
+            wins: 0,
+            score: 0,
+        });
+    }
+    let max_wins = match constraints.max_win_rate {
+        Some(rate) => {
+            let allowed = (0..=n).rev().find(|&k| (k as f64) < rate * n as f64);
+            ensure!(
+                allowed.is_some(),
+                "no strategy keeps the win rate below {}",
+                rate
+            );
+            allowed
+        }
+        None => None,
+    };
+    // A streak can't be longer than the game, or than the wins allowed.
+    let max_streak = constraints
+        .max_win_streak
+        .map(|s| s.min(n).min(max_wins.unwrap_or(n)));
+    let streaks = max_streak.map_or(1, |s| s + 1);
+    let totals = max_wins.map_or(1, |w| w + 1);
+    let idx = |streak: usize, wins: usize| streak * totals + wins;
+
+    // The state after winning a round, if that is still allowed. When a
+    // dimension is unconstrained it stays pinned at zero.
+    let after_win = |streak: usize, wins: usize| -> Option<(usize, usize)> {
+        let streak = match max_streak {
+            Some(s) if streak + 1 > s => return None,
+            Some(_) => streak + 1,
+            None => 0,
+        };
+        let wins = match max_wins {
+            Some(w) if wins + 1 > w => return None,
+            Some(_) => wins + 1,
+            None => 0,
+        };
+        Some((streak, wins))
+    };
+
+    let choices: Vec<((Shape, i64), (Shape, i64))> = opponent
+        .iter()
+        .map(|&theirs| {
+            let play = |outcome| {
+                let mine = decide_shape(theirs, outcome);
+                (mine, (score_shape(mine) + score_outcome(outcome)) as i64)
+            };
+            let win = play(Outcome::Win);
+            let (draw, loss) = (play(Outcome::Draw), play(Outcome::Loss));
+            let other = if draw.1 >= loss.1 { draw } else { loss };
+            (win, other)
+        })
+        .collect();
+
+    // next[state] is the most we can score from round i + 1 onwards, and
+    // cur[state] the same from round i; take_win holds one bit per (round,
+    // state) saying whether winning that round is best.
+    let states = streaks * totals;
+    let mut next = vec![0i64; states];
+    let mut cur = vec![0i64; states];
+    let mut take_win = vec![0u64; (n * states).div_ceil(64)];
+    for i in (0..n).rev() {
+        let ((_, win), (_, other)) = choices[i];
+        for streak in 0..streaks {
+            for wins in 0..totals {
+                let mut value = other + next[idx(0, wins)];
+                if let Some((s, w)) = after_win(streak, wins) {
+                    let won = win + next[idx(s, w)];
+                    if won >= value {
+                        value = won;
+                        let bit = i * states + idx(streak, wins);
+                        take_win[bit / 64] |= 1 << (bit % 64);
+                    }
+                }
+                cur[idx(streak, wins)] = value;
+            }
+        }
+        std::mem::swap(&mut cur, &mut next);
+    }
+    let score = next[idx(0, 0)];
+
+    let (mut streak, mut wins) = (0, 0);
+    let mut responses = Vec::with_capacity(n);
+    let mut won = 0;
+    for (i, &((win_shape, _), (other_shape, _))) in choices.iter().enumerate() {
+        let bit = i * states + idx(streak, wins);
+        if take_win[bit / 64] & (1 << (bit % 64)) != 0 {
+            responses.push(win_shape);
+            (streak, wins) = after_win(streak, wins).expect("only allowed wins are recorded");
+            won += 1;
+        } else {
+            responses.push(other_shape);
+            streak = 0;
+        }
+    }
+    Ok(CounterStrategy {
+        responses,
+        wins: won,
+        score,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day02::{parse_input1, score1};
+
+    fn check(opponent: &[Shape], constraints: Constraints) -> anyhow::Result<CounterStrategy> {
+        let strategy = counter_strategy(opponent, constraints)?;
+        let rounds: Vec<(Shape, Shape)> = opponent
+            .iter()
+            .copied()
+            .zip(strategy.responses.iter().copied())
+            .collect();
+        assert_eq!(score1::<i64>(&rounds)?, strategy.score);
+        Ok(strategy)
+    }
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        let opponent = [Shape::Rock, Shape::Paper, Shape::Scissors];
+        assert_eq!(check(&opponent, Constraints::default())?.score, 8 + 9 + 7);
+
+        let streak = Constraints {
+            max_win_streak: Some(1),
+            ..Constraints::default()
+        };
+        let strategy = check(&opponent, streak)?;
+        assert_eq!(
+            strategy.responses,
+            vec![Shape::Paper, Shape::Paper, Shape::Rock]
+        );
+        assert_eq!((strategy.score, strategy.wins), (8 + 5 + 7, 2));
+
+        let rate = Constraints {
+            max_win_rate: Some(0.5),
+            ..Constraints::default()
+        };
+        assert_eq!(check(&opponent, rate)?.score, 8 + 5 + 6);
+
+        let never = Constraints {
+            max_win_rate: Some(0.0),
+            ..Constraints::default()
+        };
+        assert!(counter_strategy(&opponent, never).is_err());
+        let empty = counter_strategy(&[], rate)?;
+        assert_eq!((empty.responses.len(), empty.score), (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn brute_force_test() -> anyhow::Result<()> {
+        let input = std::fs::read_to_string("data/day02.input")?;
+        let opponent: Vec<Shape> = parse_input1(&input)?
+            .into_iter()
+            .map(|(theirs, _)| theirs)
+            .take(12)
+            .collect();
+        let constraints = Constraints {
+            max_win_streak: Some(2),
+            max_win_rate: Some(0.4),
+        };
+
+        let n = opponent.len();
+        let mut expected = 0;
+        for mask in 0u32..1 << n {
+            let win = |i: usize| mask & (1 << i) != 0;
+            let longest = (0..n)
+                .scan(0, |run, i| {
+                    *run = if win(i) { *run + 1 } else { 0 };
+                    Some(*run)
+                })
+                .max()
+                .unwrap_or(0);
+            if longest > 2 || mask.count_ones() as f64 >= 0.4 * n as f64 {
+                continue;
+            }
+            let rounds: Vec<(Shape, Shape)> = (0..n)
+                .map(|i| {
+                    let outcome = if win(i) { Outcome::Win } else { Outcome::Draw };
+                    (opponent[i], decide_shape(opponent[i], outcome))
+                })
+                .collect();
+            expected = expected.max(score1::<i64>(&rounds)?);
+        }
+        assert_eq!(check(&opponent, constraints)?.score, expected);
+        Ok(())
+    }
+}
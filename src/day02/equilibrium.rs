@@ -0,0 +1,180 @@
+use anyhow::{bail, ensure};
+
+use super::game::Game;
+
+const EPSILON: f64 = 1e-9;
+
+/// The solution of a two-player zero-sum game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equilibrium {
+    /// How often the row player (us) should play each of their options.
+    pub row: Vec<f64>,
+    /// How often the column player (the opponent) should play each of theirs.
+    pub column: Vec<f64>,
+    /// The score the row player can guarantee per round, on average.
+    pub value: f64,
+}
+
+/// Our score for every (mine, theirs) pair of weapons, treating whatever we
+/// score as exactly what the opponent loses.
+pub fn payoff_matrix(game: &Game) -> Vec<Vec<f64>> {
+    (0..game.size())
+        .map(|mine| {
+            (0..game.size())
+                .map(|theirs| game.round_score(mine, game.outcome(theirs, mine)) as f64)
+                .collect()
+        })
+        .collect()
+}
+
+pub fn equilibrium(game: &Game) -> anyhow::Result<Equilibrium> {
+    solve_zero_sum(&payoff_matrix(game))
+}
+
+/// Finds optimal mixed strategies for a zero-sum game in which the row
+/// player receives `payoff[i][j]` from the column player.
+///
+/// After shifting every payoff to be positive, the column player's problem is
+/// the linear program `maximize sum(y) subject to A y <= 1, y >= 0`, which we
+/// solve with the simplex method; the row player's strategy falls out of the
+/// dual values of the final tableau.
+pub fn solve_zero_sum(payoff: &[Vec<f64>]) -> anyhow::Result<Equilibrium> {
+    let m = payoff.len();
+    ensure!(m > 0, "payoff matrix is empty");
+    let n = payoff[0].len();
+    ensure!(n > 0, "payoff matrix is empty");
+    ensure!(
+        payoff.iter().all(|row| row.len() == n),
+        "payoff matrix rows have different lengths"
+    );
+    let min = payoff
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let shift = 1.0 - min;
+
+    // Each constraint row is [A | I | 1]; the final row holds the negated
+    // objective coefficients and, in its last cell, the objective value.
+    let width = n + m + 1;
+    let mut tableau = vec![vec![0.0; width]; m + 1];
+    for i in 0..m {
+        for j in 0..n {
+            tableau[i][j] = payoff[i][j] + shift;
+        }
+        tableau[i][n + i] = 1.0;
+        tableau[i][width - 1] = 1.0;
+    }
+    for x in &mut tableau[m][..n] {
+        *x = -1.0;
+    }
+    let mut basis: Vec<usize> = (n..n + m).collect();
+
+    // Bland's rule: the lowest-indexed improving column, and among tied
+    // leaving rows the one with the lowest-indexed basic variable.
+    while let Some(col) = (0..width - 1).find(|&j| tableau[m][j] < -EPSILON) {
+        let row = (0..m)
+            .filter(|&i| tableau[i][col] > EPSILON)
+            .min_by(|&a, &b| {
+                let ra = tableau[a][width - 1] / tableau[a][col];
+                let rb = tableau[b][width - 1] / tableau[b][col];
+                ra.total_cmp(&rb).then(basis[a].cmp(&basis[b]))
+            });
+        let Some(row) = row else {
+            bail!("linear program is unbounded");
+        };
+        let pivot = tableau[row][col];
+        for x in tableau[row].iter_mut() {
+            *x /= pivot;
+        }
+        let pivot_row = tableau[row].clone();
+        for (i, r) in tableau.iter_mut().enumerate() {
+            let factor = r[col];
+            if i != row && factor.abs() > EPSILON {
+                for (x, p) in r.iter_mut().zip(&pivot_row) {
+                    *x -= factor * p;
+                }
+            }
+        }
+        basis[row] = col;
+    }
+
+    let total = tableau[m][width - 1];
+    ensure!(total > EPSILON, "linear program has no solution");
+    let value = 1.0 / total;
+    let mut column = vec![0.0; n];
+    for (i, &var) in basis.iter().enumerate() {
+        if var < n {
+            column[var] = tableau[i][width - 1] * value;
+        }
+    }
+    let row = (0..m).map(|i| tableau[m][n + i] * value).collect();
+    Ok(Equilibrium {
+        row,
+        column,
+        value: value - shift,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_optimal(payoff: &[Vec<f64>], eq: &Equilibrium) {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+        assert!(close(eq.row.iter().sum(), 1.0));
+        assert!(close(eq.column.iter().sum(), 1.0));
+        assert!(eq.row.iter().chain(&eq.column).all(|&p| p > -1e-9));
+        // Neither player can do better by switching to a pure strategy.
+        for j in 0..payoff[0].len() {
+            let score: f64 = (0..payoff.len()).map(|i| eq.row[i] * payoff[i][j]).sum();
+            assert!(score > eq.value - 1e-6);
+        }
+        for (i, row) in payoff.iter().enumerate() {
+            let score: f64 = row.iter().zip(&eq.column).map(|(a, q)| a * q).sum();
+            assert!(score < eq.value + 1e-6, "row {} beats the value", i);
+        }
+    }
+
+    #[test]
+    fn matching_pennies_test() -> anyhow::Result<()> {
+        let payoff = vec![vec![1.0, -1.0], vec![-1.0, 1.0]];
+        let eq = solve_zero_sum(&payoff)?;
+        assert_optimal(&payoff, &eq);
+        assert!(eq.value.abs() < 1e-9);
+        assert!((eq.row[0] - 0.5).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn saddle_point_test() -> anyhow::Result<()> {
+        let payoff = vec![vec![3.0, 5.0], vec![1.0, 2.0]];
+        let eq = solve_zero_sum(&payoff)?;
+        assert_optimal(&payoff, &eq);
+        assert!((eq.value - 3.0).abs() < 1e-9);
+        assert!((eq.row[0] - 1.0).abs() < 1e-9);
+        assert!((eq.column[0] - 1.0).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn rock_paper_scissors_test() -> anyhow::Result<()> {
+        let game = Game::rock_paper_scissors();
+        let payoff = payoff_matrix(&game);
+        assert_eq!(payoff[0], vec![4.0, 1.0, 7.0]);
+        let eq = equilibrium(&game)?;
+        assert_optimal(&payoff, &eq);
+        // Playing uniformly at random is still optimal for us and is worth 5
+        // points a round, but the opponent's best defence is lopsided: Paper
+        // loses to Scissors, our highest scoring shape, so they rarely play it.
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-9);
+        assert!(close(&eq.row, &[1.0 / 3.0; 3]));
+        assert!(close(&eq.column, &[4.0 / 9.0, 1.0 / 9.0, 4.0 / 9.0]));
+        assert!((eq.value - 5.0).abs() < 1e-9);
+
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let payoff = payoff_matrix(&game);
+        assert_optimal(&payoff, &equilibrium(&game)?);
+        Ok(())
+    }
+}
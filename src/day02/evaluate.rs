@@ -0,0 +1,141 @@
+use std::fmt;
+
+use super::{
+    decide_outcome, parse_input1, parse_input2, score1, score2,
+    strategy::{Rng, Strategy},
+    Outcome, Shape,
+};
+
+/// How one strategy fared against the opponent's column of a guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrategyScore {
+    pub name: String,
+    pub score: i64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    /// What following the guide scores under each part's reading.
+    pub part1: i64,
+    pub part2: i64,
+    pub strategies: Vec<StrategyScore>,
+}
+
+/// Plays each strategy against the opponent's moves from a strategy guide, in
+/// order, and scores it the same way as the guide itself.
+pub fn evaluate(
+    raw: &str,
+    strategies: &mut [Box<dyn Strategy>],
+    seed: u64,
+) -> anyhow::Result<Evaluation> {
+    let guide1 = parse_input1(raw)?;
+    let guide2 = parse_input2(raw)?;
+    let opponent: Vec<Shape> = guide1.iter().map(|&(theirs, _)| theirs).collect();
+    let strategies = strategies
+        .iter_mut()
+        .map(|strategy| replay(strategy.as_mut(), &opponent, seed))
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Evaluation {
+        part1: score1(&guide1)?,
+        part2: score2(&guide2)?,
+        strategies,
+    })
+}
+
+/// Plays `strategy` against a fixed sequence of opponent moves.
+pub fn replay(
+    strategy: &mut dyn Strategy,
+    opponent: &[Shape],
+    seed: u64,
+) -> anyhow::Result<StrategyScore> {
+    let mut rng = Rng::new(seed);
+    strategy.reset();
+    let mut rounds = Vec::with_capacity(opponent.len());
+    let (mut wins, mut draws, mut losses) = (0, 0, 0);
+    for &theirs in opponent {
+        let mine = strategy.choose(&mut rng);
+        strategy.observe(mine, theirs);
+        match decide_outcome(theirs, mine) {
+            Outcome::Win => wins += 1,
+            Outcome::Draw => draws += 1,
+            Outcome::Loss => losses += 1,
+        }
+        rounds.push((theirs, mine));
+    }
+    Ok(StrategyScore {
+        name: strategy.name(),
+        score: score1(&rounds)?,
+        wins,
+        draws,
+        losses,
+    })
+}
+
+impl fmt::Display for Evaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>8} {:>8} {:>8} {:>6} {:>6} {:>6}",
+            "strategy", "score", "vs p1", "vs p2", "won", "drawn", "lost"
+        )?;
+        for s in &self.strategies {
+            writeln!(
+                f,
+                "{:<20} {:>8} {:>+8} {:>+8} {:>6} {:>6} {:>6}",
+                s.name,
+                s.score,
+                s.score - self.part1,
+                s.score - self.part2,
+                s.wins,
+                s.draws,
+                s.losses
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day02::strategy::{all_strategies, Fixed};
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        let mut strategies: Vec<Box<dyn Strategy>> = vec![Box::new(Fixed::new(vec![
+            Shape::Paper,
+            Shape::Rock,
+            Shape::Scissors,
+        ])?)];
+        let eval = evaluate("A Y\nB X\nC Z", &mut strategies, 0)?;
+        assert_eq!((eval.part1, eval.part2), (15, 12));
+        // Replaying the guide's own responses reproduces its score.
+        assert_eq!(
+            eval.strategies,
+            vec![StrategyScore {
+                name: "fixed (3 shapes)".to_owned(),
+                score: 15,
+                wins: 1,
+                draws: 1,
+                losses: 1,
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn real_input_test() -> anyhow::Result<()> {
+        let input = std::fs::read_to_string("data/day02.input")?;
+        let eval = evaluate(&input, &mut all_strategies(), 42)?;
+        assert_eq!((eval.part1, eval.part2), (15337, 11696));
+        for s in &eval.strategies {
+            assert_eq!(s.wins + s.draws + s.losses, 2500);
+        }
+        assert_eq!(eval, evaluate(&input, &mut all_strategies(), 42)?);
+        assert!(eval.to_string().lines().count() == eval.strategies.len() + 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::{anyhow, bail, ensure};
+
+use super::{score_outcome, score_shape, Outcome, Shape};
+use crate::num::{checked_sum, Total};
+
+/// A Rock Paper Scissors style game: every weapon has a score, and each pair
+/// of distinct weapons has exactly one winner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    scores: Vec<u8>,
+    /// `beats[a][b]` is true when weapon `a` defeats weapon `b`.
+    beats: Vec<Vec<bool>>,
+}
+
+/// The outcome for `mine` against `theirs` when each of `n` weapons defeats
+/// the `(n - 1) / 2` weapons that precede it, wrapping around. Weapons are
+/// numbered modulo `n`, which must be positive.
+pub(super) fn cyclic_outcome(n: usize, theirs: usize, mine: usize) -> Outcome {
+    match (mine % n + n - theirs % n) % n {
+        0 => Outcome::Draw,
+        d if d <= n / 2 => Outcome::Win,
+        _ => Outcome::Loss,
+    }
+}
+
+impl Game {
+    pub fn new(names: Vec<String>, scores: Vec<u8>, beats: Vec<Vec<bool>>) -> anyhow::Result<Self> {
+        let n = names.len();
+        ensure!(n > 0, "a game needs at least one weapon");
+        ensure!(
+            scores.len() == n,
+            "{} weapons but {} scores",
+            n,
+            scores.len()
+        );
+        ensure!(
+            beats.len() == n && beats.iter().all(|row| row.len() == n),
+            "beats relation must be a {}x{} table",
+            n,
+            n
+        );
+        if let Some(&s) = scores
+            .iter()
+            .find(|&&s| s > u8::MAX - score_outcome(Outcome::Win))
+        {
+            bail!("weapon score {} is too large", s);
+        }
+        for a in 0..n {
+            ensure!(!beats[a][a], "{} cannot beat itself", names[a]);
+            for b in a + 1..n {
+                ensure!(
+                    beats[a][b] != beats[b][a],
+                    "exactly one of {} and {} must beat the other",
+                    names[a],
+                    names[b]
+                );
+            }
+        }
+        Ok(Game {
+            names,
+            scores,
+            beats,
+        })
+    }
+
+    /// A game with an odd number of weapons where each one beats the half of
+    /// the others that precede it in `names`, wrapping around.
+    pub fn cyclic(names: &[&str], scores: &[u8]) -> anyhow::Result<Self> {
+        let n = names.len();
+        ensure!(
+            n % 2 == 1,
+            "cyclic games need an odd number of weapons, not {}",
+            n
+        );
+        let beats = (0..n)
+            .map(|a| {
+                (0..n)
+                    .map(|b| cyclic_outcome(n, b, a) == Outcome::Win)
+                    .collect()
+            })
+            .collect();
+        Game::new(
+            names.iter().map(|s| s.to_string()).collect(),
+            scores.to_vec(),
+            beats,
+        )
+    }
+
+    /// A cyclic game of `n` anonymous weapons, scored 1 through `n`.
+    pub fn numbered(n: usize) -> anyhow::Result<Self> {
+        let names: Vec<String> = (1..=n).map(|i| format!("weapon {}", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let scores = (1..=n)
+            .map(|s| u8::try_from(s).map_err(|_| anyhow!("too many weapons: {}", n)))
+            .collect::<anyhow::Result<Vec<u8>>>()?;
+        Game::cyclic(&names, &scores)
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Game::cyclic(&["Rock", "Paper", "Scissors"], &Shape::ALL.map(score_shape))
+            .expect("rock paper scissors is a valid game")
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Game::cyclic(
+            &["Rock", "Spock", "Paper", "Lizard", "Scissors"],
+            &[1, 5, 2, 4, 3],
+        )
+        .expect("rock paper scissors lizard spock is a valid game")
+    }
+
+    pub fn size(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, weapon: usize) -> &str {
+        &self.names[weapon]
+    }
+
+    pub fn shape_score(&self, weapon: usize) -> u8 {
+        self.scores[weapon]
+    }
+
+    pub fn beats(&self, a: usize, b: usize) -> bool {
+        self.beats[a][b]
+    }
+
+    pub fn outcome(&self, theirs: usize, mine: usize) -> Outcome {
+        if self.beats[mine][theirs] {
+            Outcome::Win
+        } else if self.beats[theirs][mine] {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// The highest scoring weapon that produces `outcome` against `theirs`,
+    /// if there is one.
+    pub fn response(&self, theirs: usize, outcome: Outcome) -> Option<usize> {
+        (0..self.size())
+            .filter(|&mine| self.outcome(theirs, mine) == outcome)
+            .max_by_key(|&mine| (self.scores[mine], std::cmp::Reverse(mine)))
+    }
+
+    pub fn round_score(&self, mine: usize, outcome: Outcome) -> u8 {
+        self.scores[mine] + score_outcome(outcome)
+    }
+
+    /// Total score when both players' weapons are known.
+    pub fn score1<T: Total>(
+        &self,
+        rounds: impl IntoIterator<Item = (usize, usize)>,
+    ) -> anyhow::Result<T> {
+        let scores = rounds
+            .into_iter()
+            .map(|(theirs, mine)| {
+                self.check(theirs)?;
+                self.check(mine)?;
+                Ok(T::from(self.round_score(mine, self.outcome(theirs, mine))))
+            })
+            .collect::<anyhow::Result<Vec<T>>>()?;
+        Ok(checked_sum(scores)?)
+    }
+
+    /// Total score when each round's outcome is known and we have to pick a
+    /// weapon to achieve it.
+    pub fn score2<T: Total>(
+        &self,
+        rounds: impl IntoIterator<Item = (usize, Outcome)>,
+    ) -> anyhow::Result<T> {
+        let scores = rounds
+            .into_iter()
+            .map(|(theirs, outcome)| {
+                self.check(theirs)?;
+                let mine = self.response(theirs, outcome).ok_or_else(|| {
+                    anyhow!(
+                        "no weapon gives a {:?} against {}",
+                        outcome,
+                        self.names[theirs]
+                    )
+                })?;
+                Ok(T::from(self.round_score(mine, outcome)))
+            })
+            .collect::<anyhow::Result<Vec<T>>>()?;
+        Ok(checked_sum(scores)?)
+    }
+
+    fn check(&self, weapon: usize) -> anyhow::Result<()> {
+        ensure!(
+            weapon < self.size(),
+            "weapon {} is not part of a {}-weapon game",
+            weapon,
+            self.size()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day02::{decide_outcome, decide_shape};
+
+    #[test]
+    fn rock_paper_scissors_test() {
+        let game = Game::rock_paper_scissors();
+        for theirs in Shape::ALL {
+            for mine in Shape::ALL {
+                assert_eq!(
+                    game.outcome(theirs.index(), mine.index()),
+                    decide_outcome(theirs, mine)
+                );
+            }
+            for outcome in [Outcome::Win, Outcome::Loss, Outcome::Draw] {
+                assert_eq!(
+                    game.response(theirs.index(), outcome),
+                    Some(decide_shape(theirs, outcome).index())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cyclic_outcome_test() {
+        assert_eq!(cyclic_outcome(3, 7, 0), Outcome::Loss);
+        assert_eq!(cyclic_outcome(3, 7, 2), cyclic_outcome(3, 1, 2));
+        assert_eq!(cyclic_outcome(5, 0, 10), Outcome::Draw);
+    }
+
+    #[test]
+    fn lizard_spock_test() -> anyhow::Result<()> {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let idx = |name| (0..game.size()).find(|&i| game.name(i) == name).unwrap();
+        for (a, b) in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            assert!(game.beats(idx(a), idx(b)), "{} should beat {}", a, b);
+        }
+        // Both Paper (2) and Spock (5) beat Rock; Spock scores higher.
+        assert_eq!(game.response(idx("Rock"), Outcome::Win), Some(idx("Spock")));
+        assert_eq!(
+            game.score2::<i32>([(idx("Rock"), Outcome::Win), (idx("Rock"), Outcome::Draw)])?,
+            11 + 4
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn larger_games_test() -> anyhow::Result<()> {
+        for n in [7, 15] {
+            let game = Game::numbered(n)?;
+            for a in 0..n {
+                let wins = (0..n).filter(|&b| game.beats(a, b)).count();
+                assert_eq!(wins, (n - 1) / 2);
+            }
+        }
+        assert!(Game::numbered(4).is_err());
+        assert!(Game::numbered(7)?.score1::<i32>([(0, 7)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_relation_test() {
+        let names = vec!["A".to_owned(), "B".to_owned()];
+        let err = Game::new(
+            names,
+            vec![1, 2],
+            vec![vec![false, true], vec![true, false]],
+        );
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "exactly one of A and B must beat the other"
+        );
+    }
+}
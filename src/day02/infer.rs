@@ -0,0 +1,135 @@
+use std::fmt;
+
+use super::{
+    cipher::{Cipher, Symbol},
+    score1, score2, Outcome, Shape,
+};
+
+const RESPONSE_SYMBOLS: [char; 3] = ['X', 'Y', 'Z'];
+
+/// One way of reading the second column of a strategy guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoding {
+    Shapes(Cipher<Shape>),
+    Outcomes(Cipher<Outcome>),
+}
+
+impl fmt::Display for Decoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decoding::Shapes(c) => write!(f, "shapes: {}", c),
+            Decoding::Outcomes(c) => write!(f, "outcomes: {}", c),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub decoding: Decoding,
+    pub score: i64,
+}
+
+/// Every reading of X/Y/Z, either as shapes or as outcomes, under which the
+/// guide scores exactly `target`.
+pub fn infer_ciphers(raw: &str, target: i64) -> anyhow::Result<Vec<Candidate>> {
+    infer_ciphers_by(raw, |score| score == target)
+}
+
+/// Every reading of X/Y/Z, either as shapes or as outcomes, whose score the
+/// `accept` predicate agrees with.
+pub fn infer_ciphers_by(raw: &str, accept: impl Fn(i64) -> bool) -> anyhow::Result<Vec<Candidate>> {
+    let mut acc = Vec::new();
+    for cipher in ciphers(Shape::ALL)? {
+        let score = score1(&cipher.parse_guide(raw)?)?;
+        if accept(score) {
+            acc.push(Candidate {
+                decoding: Decoding::Shapes(cipher),
+                score,
+            });
+        }
+    }
+    for cipher in ciphers([Outcome::Loss, Outcome::Draw, Outcome::Win])? {
+        let score = score2(&cipher.parse_guide(raw)?)?;
+        if accept(score) {
+            acc.push(Candidate {
+                decoding: Decoding::Outcomes(cipher),
+                score,
+            });
+        }
+    }
+    Ok(acc)
+}
+
+/// A cipher for every assignment of `values` to X, Y and Z, keeping the
+/// opponent's column as A, B and C.
+fn ciphers<T: Symbol>(values: [T; 3]) -> anyhow::Result<Vec<Cipher<T>>> {
+    let opponent = Cipher::shapes().opponent().clone();
+    permutations(&values)
+        .into_iter()
+        .map(|perm| Cipher::new(opponent.clone(), RESPONSE_SYMBOLS.into_iter().zip(perm)))
+        .collect()
+}
+
+fn permutations<T: Copy>(xs: &[T]) -> Vec<Vec<T>> {
+    if xs.is_empty() {
+        return vec![Vec::new()];
+    }
+    let mut acc = Vec::new();
+    for i in 0..xs.len() {
+        let mut rest = xs.to_vec();
+        let first = rest.remove(i);
+        for mut perm in permutations(&rest) {
+            perm.insert(0, first);
+            acc.push(perm);
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        let input = "A Y\nB X\nC Z";
+        let found = infer_ciphers(input, 15)?;
+        assert!(found.contains(&Candidate {
+            decoding: Decoding::Shapes(Cipher::shapes()),
+            score: 15,
+        }));
+        assert!(found.iter().all(|c| c.score == 15));
+
+        let found = infer_ciphers(input, 12)?;
+        assert!(found.contains(&Candidate {
+            decoding: Decoding::Outcomes(Cipher::outcomes()),
+            score: 12,
+        }));
+
+        assert_eq!(infer_ciphers_by(input, |_| true)?.len(), 12);
+        assert!(infer_ciphers(input, 1000)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn real_input_test() -> anyhow::Result<()> {
+        let input = std::fs::read_to_string("data/day02.input")?;
+        let found = infer_ciphers(&input, 15337)?;
+        assert_eq!(
+            found,
+            vec![Candidate {
+                decoding: Decoding::Shapes(Cipher::shapes()),
+                score: 15337,
+            }]
+        );
+        let found = infer_ciphers(&input, 11696)?;
+        assert_eq!(
+            found
+                .iter()
+                .map(|c| c.decoding.to_string())
+                .collect::<Vec<_>>(),
+            vec!["outcomes: A=Rock B=Paper C=Scissors; X=Loss Y=Draw Z=Win"]
+        );
+        Ok(())
+    }
+}
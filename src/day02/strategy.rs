@@ -0,0 +1,340 @@
+use std::num::NonZeroUsize;
+
+use anyhow::ensure;
+
+use super::{decide_outcome, decide_shape, Outcome, Shape};
+
+/// A small deterministic random number generator (SplitMix64), so that
+/// simulations can be replayed from a seed.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: NonZeroUsize) -> usize {
+        (self.next_u64() % n.get() as u64) as usize
+    }
+
+    pub fn shape(&mut self) -> Shape {
+        const SHAPES: NonZeroUsize = NonZeroUsize::new(Shape::ALL.len()).unwrap();
+        Shape::ALL[self.below(SHAPES)]
+    }
+}
+
+/// A way of playing repeated rounds against the same opponent.
+pub trait Strategy {
+    fn name(&self) -> String;
+    /// Picks the shape to play this round.
+    fn choose(&mut self, rng: &mut Rng) -> Shape;
+    /// Learns what both players played in the round that just finished.
+    fn observe(&mut self, mine: Shape, theirs: Shape);
+    /// Forgets everything learned so far, ready for a new opponent.
+    fn reset(&mut self);
+}
+
+/// The shape that beats `shape`.
+pub fn counter(shape: Shape) -> Shape {
+    decide_shape(shape, Outcome::Win)
+}
+
+/// Plays a fixed sequence of shapes, starting over when it runs out.
+#[derive(Debug, Clone)]
+pub struct Fixed {
+    shapes: Vec<Shape>,
+    next: usize,
+}
+
+impl Fixed {
+    pub fn new(shapes: Vec<Shape>) -> anyhow::Result<Self> {
+        ensure!(
+            !shapes.is_empty(),
+            "a fixed strategy needs at least one shape"
+        );
+        Ok(Fixed { shapes, next: 0 })
+    }
+
+    /// Plays the response column of a part 1 strategy guide.
+    pub fn from_guide(guide: &[(Shape, Shape)]) -> anyhow::Result<Self> {
+        Fixed::new(guide.iter().map(|&(_, mine)| mine).collect())
+    }
+}
+
+impl Strategy for Fixed {
+    fn name(&self) -> String {
+        match &self.shapes[..] {
+            [shape] => format!("always {}", shape),
+            shapes => format!("fixed ({} shapes)", shapes.len()),
+        }
+    }
+    fn choose(&mut self, _rng: &mut Rng) -> Shape {
+        let shape = self.shapes[self.next];
+        self.next = (self.next + 1) % self.shapes.len();
+        shape
+    }
+    fn observe(&mut self, _mine: Shape, _theirs: Shape) {}
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+/// Plays uniformly at random.
+#[derive(Debug, Clone, Default)]
+pub struct Random;
+
+impl Strategy for Random {
+    fn name(&self) -> String {
+        "random".to_owned()
+    }
+    fn choose(&mut self, rng: &mut Rng) -> Shape {
+        rng.shape()
+    }
+    fn observe(&mut self, _mine: Shape, _theirs: Shape) {}
+    fn reset(&mut self) {}
+}
+
+/// The most common shape in `counts`, breaking ties at random, or a random
+/// shape if nothing has been counted.
+fn most_likely(counts: &[usize; 3], rng: &mut Rng) -> Shape {
+    let best = counts.iter().copied().max().unwrap_or(0);
+    let candidates: Vec<Shape> = Shape::ALL
+        .into_iter()
+        .filter(|s| counts[s.index()] == best)
+        .collect();
+    let n = NonZeroUsize::new(candidates.len()).expect("the most common shape is a candidate");
+    candidates[rng.below(n)]
+}
+
+/// Assumes the opponent favours whatever they have played most often so far,
+/// and plays the shape that beats it.
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyCounter {
+    counts: [usize; 3],
+}
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> String {
+        "frequency counter".to_owned()
+    }
+    fn choose(&mut self, rng: &mut Rng) -> Shape {
+        counter(most_likely(&self.counts, rng))
+    }
+    fn observe(&mut self, _mine: Shape, theirs: Shape) {
+        self.counts[theirs.index()] += 1;
+    }
+    fn reset(&mut self) {
+        self.counts = [0; 3];
+    }
+}
+
+/// Predicts the opponent's next shape from what they have played in the past
+/// after their last `order` shapes, and plays the shape that beats it. With
+/// an order of 1 this is a Markov chain over single shapes.
+#[derive(Debug, Clone)]
+pub struct NGram {
+    order: usize,
+    /// Counts of the opponent's next shape, indexed by their last `order`
+    /// shapes read as a base 3 number.
+    counts: Vec<[usize; 3]>,
+    history: Vec<Shape>,
+}
+
+impl NGram {
+    /// The longest context allowed; the table of counts has `3^order` rows.
+    pub const MAX_ORDER: usize = 10;
+
+    pub fn new(order: usize) -> anyhow::Result<Self> {
+        ensure!(
+            order > 0,
+            "an n-gram model needs at least one shape of context"
+        );
+        ensure!(
+            order <= Self::MAX_ORDER,
+            "an n-gram model can use at most {} shapes of context, got {}",
+            Self::MAX_ORDER,
+            order
+        );
+        Ok(NGram::empty(order))
+    }
+
+    pub fn markov() -> Self {
+        NGram::empty(1)
+    }
+
+    /// A model that has seen nothing yet; `order` must already be in range.
+    fn empty(order: usize) -> Self {
+        NGram {
+            order,
+            counts: vec![[0; 3]; 3usize.pow(order as u32)],
+            history: Vec::new(),
+        }
+    }
+
+    fn context(&self) -> Option<usize> {
+        let start = self.history.len().checked_sub(self.order)?;
+        Some(
+            self.history[start..]
+                .iter()
+                .fold(0, |acc, s| acc * 3 + s.index()),
+        )
+    }
+}
+
+impl Strategy for NGram {
+    fn name(&self) -> String {
+        match self.order {
+            1 => "markov".to_owned(),
+            n => format!("{}-gram", n + 1),
+        }
+    }
+    fn choose(&mut self, rng: &mut Rng) -> Shape {
+        let counts = match self.context() {
+            Some(ctx) => self.counts[ctx],
+            None => [0; 3],
+        };
+        counter(most_likely(&counts, rng))
+    }
+    fn observe(&mut self, _mine: Shape, theirs: Shape) {
+        if let Some(ctx) = self.context() {
+            self.counts[ctx][theirs.index()] += 1;
+        }
+        self.history.push(theirs);
+        if self.history.len() > self.order {
+            self.history.remove(0);
+        }
+    }
+    fn reset(&mut self) {
+        *self = NGram::empty(self.order);
+    }
+}
+
+/// Keeps playing the same shape while it wins; otherwise switches to the shape
+/// that would have beaten the opponent's last move.
+#[derive(Debug, Clone, Default)]
+pub struct WinStayLoseShift {
+    next: Option<Shape>,
+}
+
+impl Strategy for WinStayLoseShift {
+    fn name(&self) -> String {
+        "win-stay lose-shift".to_owned()
+    }
+    fn choose(&mut self, rng: &mut Rng) -> Shape {
+        self.next.unwrap_or_else(|| rng.shape())
+    }
+    fn observe(&mut self, mine: Shape, theirs: Shape) {
+        self.next = Some(match decide_outcome(theirs, mine) {
+            Outcome::Win => mine,
+            Outcome::Draw | Outcome::Loss => counter(theirs),
+        });
+    }
+    fn reset(&mut self) {
+        self.next = None;
+    }
+}
+
+/// One of each kind of strategy, for comparing them against each other.
+pub fn all_strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(Random),
+        Box::new(FrequencyCounter::default()),
+        Box::new(NGram::markov()),
+        Box::new(NGram::empty(2)),
+        Box::new(WinStayLoseShift::default()),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn play(strategy: &mut dyn Strategy, opponent: &[Shape]) -> Vec<Shape> {
+        let mut rng = Rng::new(7);
+        opponent
+            .iter()
+            .map(|&theirs| {
+                let mine = strategy.choose(&mut rng);
+                strategy.observe(mine, theirs);
+                mine
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rng_test() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let xs: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let ys: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+        assert_eq!(xs, ys);
+        assert_ne!(xs[0], Rng::new(43).next_u64());
+        let three = NonZeroUsize::new(3).unwrap();
+        assert!((0..100).all(|_| a.below(three) < 3));
+    }
+
+    #[test]
+    fn frequency_counter_test() {
+        let opponent = [Shape::Rock; 5];
+        let played = play(&mut FrequencyCounter::default(), &opponent);
+        assert!(played[1..].iter().all(|&s| s == Shape::Paper));
+    }
+
+    #[test]
+    fn ngram_test() -> anyhow::Result<()> {
+        let opponent: Vec<Shape> = Shape::ALL.iter().copied().cycle().take(12).collect();
+        let played = play(&mut NGram::markov(), &opponent);
+        // Once every transition has been seen, it always wins.
+        for i in 4..12 {
+            assert_eq!(played[i], counter(opponent[i]));
+        }
+
+        // Rock, Rock, Paper repeats: one shape of context can't tell what
+        // follows Rock, but two can.
+        let pattern = [Shape::Rock, Shape::Rock, Shape::Paper];
+        let opponent: Vec<Shape> = pattern.iter().copied().cycle().take(30).collect();
+        let played = play(&mut NGram::new(2)?, &opponent);
+        for i in 12..30 {
+            assert_eq!(played[i], counter(opponent[i]));
+        }
+
+        assert!(NGram::new(0).is_err());
+        assert!(NGram::new(NGram::MAX_ORDER).is_ok());
+        assert!(NGram::new(NGram::MAX_ORDER + 1).is_err());
+        assert!(NGram::new(usize::MAX).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn win_stay_lose_shift_test() {
+        let mut wsls = WinStayLoseShift::default();
+        wsls.observe(Shape::Paper, Shape::Rock);
+        assert_eq!(wsls.choose(&mut Rng::new(0)), Shape::Paper);
+        wsls.observe(Shape::Paper, Shape::Scissors);
+        assert_eq!(wsls.choose(&mut Rng::new(0)), Shape::Rock);
+        wsls.observe(Shape::Rock, Shape::Rock);
+        assert_eq!(wsls.choose(&mut Rng::new(0)), Shape::Paper);
+    }
+
+    #[test]
+    fn fixed_test() -> anyhow::Result<()> {
+        let mut fixed = Fixed::new(vec![Shape::Rock, Shape::Scissors])?;
+        let played = play(&mut fixed, &[Shape::Paper; 3]);
+        assert_eq!(played, vec![Shape::Rock, Shape::Scissors, Shape::Rock]);
+        fixed.reset();
+        assert_eq!(fixed.choose(&mut Rng::new(0)), Shape::Rock);
+        assert!(Fixed::new(Vec::new()).is_err());
+        assert!(Fixed::from_guide(&[]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+use std::fmt;
+
+use anyhow::{ensure, Context};
+
+use super::{
+    decide_outcome, score1,
+    strategy::{Fixed, Rng, Strategy},
+    Outcome, Shape,
+};
+
+pub struct Player {
+    pub name: String,
+    strategy: Box<dyn Strategy>,
+}
+
+/// What happened when two players met for a fixed number of rounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub players: (usize, usize),
+    pub scores: (i64, i64),
+    /// Rounds won by each player; the rest were draws.
+    pub wins: (usize, usize),
+    pub draws: usize,
+}
+
+impl MatchResult {
+    /// The player with the higher total score, if there is one.
+    pub fn winner(&self) -> Option<usize> {
+        match self.scores.0.cmp(&self.scores.1) {
+            std::cmp::Ordering::Greater => Some(self.players.0),
+            std::cmp::Ordering::Less => Some(self.players.1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub player: usize,
+    pub name: String,
+    pub played: usize,
+    pub won: usize,
+    pub drawn: usize,
+    pub lost: usize,
+    /// 3 points for a match win and 1 for a draw.
+    pub points: usize,
+    /// Total score over every round played.
+    pub score: i64,
+}
+
+/// Players ordered by points, then total score, then entry order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standings(pub Vec<Standing>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundRobin {
+    pub matches: Vec<MatchResult>,
+    pub standings: Standings,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BracketRound {
+    pub matches: Vec<MatchResult>,
+    /// Players who advanced without playing.
+    pub byes: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bracket {
+    pub rounds: Vec<BracketRound>,
+    pub champion: usize,
+}
+
+/// A set of players who play each other for `rounds` rounds per match.
+///
+/// Every match draws its randomness from the tournament seed and the pair of
+/// players involved, so results are reproducible and do not depend on the
+/// order matches are played in.
+pub struct Tournament {
+    players: Vec<Player>,
+    rounds: usize,
+    seed: u64,
+}
+
+impl Tournament {
+    pub fn new(rounds: usize, seed: u64) -> Self {
+        Tournament {
+            players: Vec::new(),
+            rounds,
+            seed,
+        }
+    }
+
+    pub fn add_player(&mut self, name: &str, strategy: impl Strategy + 'static) -> usize {
+        self.players.push(Player {
+            name: name.to_owned(),
+            strategy: Box::new(strategy),
+        });
+        self.players.len() - 1
+    }
+
+    /// Adds a player who follows the response column of a strategy guide.
+    pub fn add_guide(&mut self, name: &str, guide: &[(Shape, Shape)]) -> anyhow::Result<usize> {
+        let strategy =
+            Fixed::from_guide(guide).with_context(|| format!("strategy guide for {}", name))?;
+        Ok(self.add_player(name, strategy))
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// Plays a fresh match between players `a` and `b`.
+    pub fn play_match(&mut self, a: usize, b: usize) -> anyhow::Result<MatchResult> {
+        ensure!(a != b, "player {} cannot play themselves", a);
+        ensure!(
+            a.max(b) < self.players.len(),
+            "there are only {} players",
+            self.players.len()
+        );
+        let mut rng = Rng::new(self.seed ^ ((a as u64) << 32 | b as u64));
+        let (pa, pb) = if a < b {
+            let (lo, hi) = self.players.split_at_mut(b);
+            (&mut lo[a], &mut hi[0])
+        } else {
+            let (lo, hi) = self.players.split_at_mut(a);
+            (&mut hi[0], &mut lo[b])
+        };
+        pa.strategy.reset();
+        pb.strategy.reset();
+
+        // Each player's rounds, as (theirs, mine) for scoring.
+        let mut rounds_a = Vec::with_capacity(self.rounds);
+        let mut rounds_b = Vec::with_capacity(self.rounds);
+        let (mut wins, mut draws) = ((0, 0), 0);
+        for _ in 0..self.rounds {
+            let x = pa.strategy.choose(&mut rng);
+            let y = pb.strategy.choose(&mut rng);
+            pa.strategy.observe(x, y);
+            pb.strategy.observe(y, x);
+            match decide_outcome(y, x) {
+                Outcome::Win => wins.0 += 1,
+                Outcome::Loss => wins.1 += 1,
+                Outcome::Draw => draws += 1,
+            }
+            rounds_a.push((y, x));
+            rounds_b.push((x, y));
+        }
+        Ok(MatchResult {
+            players: (a, b),
+            scores: (score1(&rounds_a)?, score1(&rounds_b)?),
+            wins,
+            draws,
+        })
+    }
+
+    /// Every player meets every other player once.
+    pub fn round_robin(&mut self) -> anyhow::Result<RoundRobin> {
+        let n = self.players.len();
+        ensure!(n >= 2, "a tournament needs at least two players");
+        let mut matches = Vec::with_capacity(n * (n - 1) / 2);
+        for a in 0..n {
+            for b in a + 1..n {
+                matches.push(self.play_match(a, b)?);
+            }
+        }
+        let standings = self.standings(&matches);
+        Ok(RoundRobin { matches, standings })
+    }
+
+    /// A knockout bracket seeded in entry order: the top remaining seed meets
+    /// the bottom one, and with an odd number of players the top seed gets a
+    /// bye. A match tied on score goes to the higher seed.
+    pub fn single_elimination(&mut self) -> anyhow::Result<Bracket> {
+        ensure!(
+            self.players.len() >= 2,
+            "a tournament needs at least two players"
+        );
+        let mut remaining: Vec<usize> = (0..self.players.len()).collect();
+        let mut rounds = Vec::new();
+        while remaining.len() > 1 {
+            let mut byes = Vec::new();
+            if remaining.len() % 2 == 1 {
+                byes.push(remaining.remove(0));
+            }
+            let half = remaining.len() / 2;
+            let mut matches = Vec::with_capacity(half);
+            let mut advancing = byes.clone();
+            for i in 0..half {
+                let (a, b) = (remaining[i], remaining[remaining.len() - 1 - i]);
+                let result = self.play_match(a, b)?;
+                advancing.push(result.winner().unwrap_or(a.min(b)));
+                matches.push(result);
+            }
+            advancing.sort_unstable();
+            remaining = advancing;
+            rounds.push(BracketRound { matches, byes });
+        }
+        Ok(Bracket {
+            rounds,
+            champion: remaining[0],
+        })
+    }
+
+    /// Tallies match results into a table.
+    pub fn standings(&self, matches: &[MatchResult]) -> Standings {
+        let mut table: Vec<Standing> = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(player, p)| Standing {
+                player,
+                name: p.name.clone(),
+                played: 0,
+                won: 0,
+                drawn: 0,
+                lost: 0,
+                points: 0,
+                score: 0,
+            })
+            .collect();
+        for m in matches {
+            let winner = m.winner();
+            for (player, score) in [(m.players.0, m.scores.0), (m.players.1, m.scores.1)] {
+                let s = &mut table[player];
+                s.played += 1;
+                s.score += score;
+                match winner {
+                    Some(w) if w == player => {
+                        s.won += 1;
+                        s.points += 3;
+                    }
+                    Some(_) => s.lost += 1,
+                    None => {
+                        s.drawn += 1;
+                        s.points += 1;
+                    }
+                }
+            }
+        }
+        table.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(b.score.cmp(&a.score))
+                .then(a.player.cmp(&b.player))
+        });
+        Standings(table)
+    }
+}
+
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4} {:<20} {:>3} {:>3} {:>3} {:>3} {:>4} {:>8}",
+            "pos", "player", "p", "w", "d", "l", "pts", "score"
+        )?;
+        for (i, s) in self.0.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4} {:<20} {:>3} {:>3} {:>3} {:>3} {:>4} {:>8}",
+                i + 1,
+                s.name,
+                s.played,
+                s.won,
+                s.drawn,
+                s.lost,
+                s.points,
+                s.score
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day02::{
+        parse_input1,
+        strategy::{FrequencyCounter, NGram, Random},
+    };
+
+    fn tournament(seed: u64) -> anyhow::Result<Tournament> {
+        let mut t = Tournament::new(100, seed);
+        t.add_player("rock", Fixed::new(vec![Shape::Rock])?);
+        t.add_guide("example guide", &parse_input1("A Y\nB X\nC Z")?)?;
+        t.add_player("frequency", FrequencyCounter::default());
+        t.add_player("markov", NGram::markov());
+        t.add_player("random", Random);
+        Ok(t)
+    }
+
+    #[test]
+    fn play_match_test() -> anyhow::Result<()> {
+        let mut t = Tournament::new(10, 0);
+        let rock = t.add_player("rock", Fixed::new(vec![Shape::Rock])?);
+        let paper = t.add_player("paper", Fixed::new(vec![Shape::Paper])?);
+        let result = t.play_match(rock, paper)?;
+        assert_eq!(result.scores, (10, 80));
+        assert_eq!((result.wins, result.draws), ((0, 10), 0));
+        assert_eq!(result.winner(), Some(paper));
+        assert!(t.play_match(rock, rock).is_err());
+        let err = t.add_guide("nobody", &[]).unwrap_err();
+        assert_eq!(err.to_string(), "strategy guide for nobody");
+        assert_eq!(t.players().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn round_robin_test() -> anyhow::Result<()> {
+        let result = tournament(1)?.round_robin()?;
+        assert_eq!(result.matches.len(), 10);
+        let table = &result.standings.0;
+        assert!(table.iter().all(|s| s.played == 4));
+        assert_eq!(table.last().map(|s| s.name.as_str()), Some("rock"));
+        assert!(table.windows(2).all(|w| w[0].points >= w[1].points));
+        assert!(result.standings.to_string().starts_with(" pos player"));
+        // The same seed gives the same tournament.
+        assert_eq!(tournament(1)?.round_robin()?, result);
+        Ok(())
+    }
+
+    #[test]
+    fn single_elimination_test() -> anyhow::Result<()> {
+        let bracket = tournament(2)?.single_elimination()?;
+        // 5 players: a bye and two matches, then a bye and one match, then the final.
+        let shape: Vec<(usize, usize)> = bracket
+            .rounds
+            .iter()
+            .map(|r| (r.matches.len(), r.byes.len()))
+            .collect();
+        assert_eq!(shape, vec![(2, 1), (1, 1), (1, 0)]);
+        let last = &bracket.rounds[2].matches[0];
+        let finalists = [last.players.0, last.players.1];
+        assert!(finalists.contains(&bracket.champion));
+        assert_eq!(tournament(2)?.single_elimination()?, bracket);
+        Ok(())
+    }
+}
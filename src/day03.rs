@@ -92,81 +92,186 @@ attachment efforts: here, they are 18 (r) for the first group and 52 (Z) for the
 second group. The sum of these is 70.
 */
 
-use std::collections::BTreeSet;
+pub mod badges;
+pub mod item_set;
+pub mod rearrange;
+pub mod regroup;
 
-use anyhow::anyhow;
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::{anyhow, bail};
 use nom::{
-    character::complete::{alpha1, multispace1},
-    combinator::all_consuming,
-    multi::separated_list1,
-    IResult,
+    character::complete::alpha1,
+    combinator::{map_res, verify},
+    error::context,
 };
 
-#[derive(Debug)]
-pub struct Rucksack(String);
-pub fn parse_input(raw: &str) -> anyhow::Result<Vec<Rucksack>> {
-    match all_consuming(separated_list1(multispace1, rucksack_parser))(raw.trim()) {
-        Ok((_, lines)) => Ok(lines),
-        Err(e) => Err(anyhow!("could not parse input [{}]: {}", e, raw)),
+use crate::{
+    num::{checked_sum, Total},
+    parse::{parse_lines, ParseResult},
+};
+use badges::{find_badges, BadgeSearch};
+use item_set::ItemSet;
+
+/// An item type, identified by a letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Item(u8);
+
+impl Item {
+    /// The item with priority `p`: 1 through 26 are a-z, 27 through 52 are A-Z.
+    pub fn from_priority(p: u8) -> Option<Item> {
+        (1..=52).contains(&p).then_some(Item(p))
+    }
+
+    pub fn priority(self) -> u8 {
+        self.0
+    }
+
+    pub fn letter(self) -> char {
+        match self.0 {
+            1..=26 => (b'a' + self.0 - 1) as char,
+            _ => (b'A' + self.0 - 27) as char,
+        }
     }
 }
-fn rucksack_parser(input: &str) -> IResult<&str, Rucksack> {
-    let (input, v) = alpha1(input)?;
-    Ok((input, Rucksack(v.to_owned())))
+
+impl TryFrom<char> for Item {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> anyhow::Result<Self> {
+        match c {
+            'a'..='z' => Ok(Item(c as u8 - b'a' + 1)),
+            'A'..='Z' => Ok(Item(c as u8 - b'A' + 27)),
+            _ => Err(anyhow!("invalid item: {:?}", c)),
+        }
+    }
 }
 
-pub fn solve1(rucksacks: &[Rucksack]) -> i32 {
-    rucksacks
-        .iter()
-        .map(|r| {
-            let (c1, c2) = r.0.as_bytes().split_at(r.0.len() / 2);
-            let c1: BTreeSet<u8> = c1.iter().copied().collect();
-            let c2: BTreeSet<u8> = c2.iter().copied().collect();
-            let common: Vec<u8> = c1.intersection(&c2).copied().collect();
-            assert_eq!(
-                common.len(),
-                1,
-                "{} has the wrong number of common items",
-                r.0
-            );
-            priority(common[0]) as i32
-        })
-        .sum()
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.letter())
+    }
 }
 
-pub fn solve2(rucksacks: &[Rucksack]) -> i32 {
-    rucksacks
-        .chunks_exact(3)
-        .map(|group| {
-            let mut iter = group.iter();
-            let mut common: BTreeSet<u8> =
-                iter.next().unwrap().0.as_bytes().iter().copied().collect();
-            for r in group {
-                let items = r.0.as_bytes().iter().copied().collect();
-                common = common.intersection(&items).copied().collect();
-            }
-            assert_eq!(
-                common.len(),
-                1,
-                "{:?} has the wrong number of badges",
-                group
+/// The items in a rucksack, split evenly between its two compartments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rucksack(Vec<Item>);
+
+impl Rucksack {
+    pub fn new(items: Vec<Item>) -> anyhow::Result<Self> {
+        if !items.len().is_multiple_of(2) {
+            bail!(
+                "a rucksack needs an even number of items, got {}",
+                items.len()
             );
-            let badge = common.iter().next().unwrap();
-            priority(*badge) as i32
+        }
+        Ok(Rucksack(items))
+    }
+
+    /// Every item in the rucksack, in order.
+    pub fn items(&self) -> &[Item] {
+        &self.0
+    }
+
+    /// Every item type in the rucksack.
+    pub fn item_types(&self) -> ItemSet {
+        self.0.iter().copied().collect()
+    }
+
+    /// The item types in each compartment.
+    pub fn compartments(&self) -> (ItemSet, ItemSet) {
+        let (c1, c2) = self.0.split_at(self.0.len() / 2);
+        (c1.iter().copied().collect(), c2.iter().copied().collect())
+    }
+
+    /// The item types found in both compartments.
+    pub fn misplaced_items(&self) -> ItemSet {
+        let (c1, c2) = self.compartments();
+        c1 & c2
+    }
+
+    /// How many of each item type the rucksack holds.
+    pub fn item_counts(&self) -> BTreeMap<Item, usize> {
+        let mut acc = BTreeMap::new();
+        for &item in &self.0 {
+            *acc.entry(item).or_default() += 1;
+        }
+        acc
+    }
+
+    /// The priority of the misplaced item type, if there is exactly one.
+    pub fn priority_of(&self) -> Option<u8> {
+        self.misplaced_items().single().map(Item::priority)
+    }
+}
+
+impl fmt::Display for Rucksack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|item| write!(f, "{}", item))
+    }
+}
+
+pub fn parse_input(raw: &str) -> anyhow::Result<Vec<Rucksack>> {
+    Ok(parse_lines(3, raw, rucksack_parser)?)
+}
+fn rucksack_parser(input: &str) -> ParseResult<'_, Rucksack> {
+    let items = context("items (a-z or A-Z)", alpha1);
+    let even = context(
+        "an even number of items",
+        verify(items, |s: &str| s.len().is_multiple_of(2)),
+    );
+    map_res(even, |s: &str| {
+        Rucksack::new(
+            s.chars()
+                .map(Item::try_from)
+                .collect::<anyhow::Result<_>>()?,
+        )
+    })(input)
+}
+
+pub fn solve1<T: Total>(rucksacks: &[Rucksack]) -> anyhow::Result<T> {
+    let priorities = rucksacks
+        .iter()
+        .enumerate()
+        .map(|(i, r)| match r.priority_of() {
+            Some(p) => Ok(T::from(p)),
+            None => bail!(
+                "rucksack {} ({}) has {} common items, expected exactly 1",
+                i + 1,
+                r,
+                r.misplaced_items().len()
+            ),
         })
-        .sum()
+        .collect::<anyhow::Result<Vec<T>>>()?;
+    Ok(checked_sum(priorities)?)
 }
 
-fn priority(item: u8) -> u8 {
-    match item {
-        b'a'..=b'z' => item - b'a' + 1,
-        b'A'..=b'Z' => item - b'A' + 27,
-        _ => unreachable!("invalid item: {}", item),
+pub fn solve2<T: Total>(rucksacks: &[Rucksack]) -> anyhow::Result<T> {
+    find_badges(rucksacks, &BadgeSearch::default())?.priority_sum()
+}
+
+pub struct Day03;
+
+impl crate::Solution for Day03 {
+    type Input = Vec<Rucksack>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(raw: &str) -> anyhow::Result<Self::Input> {
+        parse_input(raw)
+    }
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        solve1(input)
+    }
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        solve2(input)
     }
 }
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::parse::ParseError;
 
     #[test]
     fn example_test() -> anyhow::Result<()> {
@@ -179,7 +284,49 @@ mod test {
             CrZsJsPPZsGzwwsLwLmpwMDw
         "#;
         let input = parse_input(input)?;
-        assert_eq!(solve1(&input), 157);
+        assert_eq!(solve1::<i32>(&input)?, 157);
+        Ok(())
+    }
+
+    #[test]
+    fn invariant_violations_test() -> anyhow::Result<()> {
+        let input = parse_input("vJrwpWtwJgWrhcsFMMfFFhFp\nabcabc\nmnopqr")?;
+        let err = solve1::<i32>(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "rucksack 2 (abcabc) has 3 common items, expected exactly 1"
+        );
+        let err = solve2::<i32>(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "group 1 (rucksacks 1-3) has 0 badges, expected exactly 1"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn odd_rucksack_test() {
+        let err = parse_input("abcd\nmnopq").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "an even number of items");
+    }
+
+    #[test]
+    fn rucksack_test() -> anyhow::Result<()> {
+        let r = &parse_input("vJrwpWtwJgWrhcsFMMfFFhFp")?[0];
+        let p = Item::try_from('p')?;
+        assert_eq!(r.priority_of(), Some(16));
+        assert_eq!(r.misplaced_items().iter().collect::<Vec<_>>(), vec![p]);
+        let (c1, c2) = r.compartments();
+        assert_eq!((c1.len(), c2.len()), (8, 7));
+        assert_eq!(r.item_counts()[&Item::try_from('F')?], 4);
+        assert_eq!(r.item_counts().values().sum::<usize>(), 24);
+        assert_eq!(r.to_string(), "vJrwpWtwJgWrhcsFMMfFFhFp");
+        assert_eq!(Item::try_from('Z')?.priority(), 52);
+        assert_eq!(Item::from_priority(27).map(Item::letter), Some('A'));
+        assert!(Item::try_from('?').is_err());
+        assert!(Rucksack::new(vec![p]).is_err());
         Ok(())
     }
 
@@ -187,7 +334,7 @@ mod test {
     fn part1() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day03.input")?;
         let input = parse_input(&input)?;
-        assert_eq!(solve1(&input), 7428);
+        assert_eq!(solve1::<u64>(&input)?, 7428);
         Ok(())
     }
 
@@ -195,7 +342,7 @@ mod test {
     fn part2() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day03.input")?;
         let input = parse_input(&input)?;
-        assert_eq!(solve2(&input), 2650);
+        assert_eq!(solve2::<i64>(&input)?, 2650);
         Ok(())
     }
 }
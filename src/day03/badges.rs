@@ -0,0 +1,299 @@
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::{bail, ensure};
+
+use super::{item_set::ItemSet, Item, Rucksack};
+use crate::num::{checked_sum, Total};
+
+/// How rucksacks are sorted into groups.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Grouping {
+    /// Consecutive runs of rucksacks, as in the puzzle.
+    #[default]
+    Chunks,
+    /// The group ID of each rucksack, in input order.
+    Ids(Vec<usize>),
+    /// Every run of consecutive rucksacks, overlapping.
+    Window,
+}
+
+/// What to do with rucksacks that don't make up a full group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Leftovers {
+    #[default]
+    Reject,
+    Report,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadgeSearch {
+    pub group_size: usize,
+    pub grouping: Grouping,
+    pub leftovers: Leftovers,
+}
+
+impl Default for BadgeSearch {
+    fn default() -> Self {
+        BadgeSearch {
+            group_size: 3,
+            grouping: Grouping::default(),
+            leftovers: Leftovers::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// Position among the groups found.
+    pub id: usize,
+    /// The caller's ID for the group, with `Grouping::Ids`.
+    pub label: Option<usize>,
+    /// Indices of the rucksacks in the group.
+    pub members: Vec<usize>,
+    /// Every item type all members carry.
+    pub common: ItemSet,
+}
+
+impl Group {
+    /// The group's badge, if its members have exactly one item type in common.
+    pub fn badge(&self) -> Option<Item> {
+        self.common.single()
+    }
+}
+
+impl fmt::Display for Group {
+    /// Names the group by the caller's ID if it has one, otherwise by its
+    /// position numbered from 1, followed by its members.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "group {} (rucksacks {})",
+            self.label.unwrap_or(self.id + 1),
+            describe(&self.members)
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Badges {
+    pub groups: Vec<Group>,
+    /// Indices of rucksacks that are not in any full group.
+    pub leftovers: Vec<usize>,
+}
+
+impl Badges {
+    /// The sum of every group's badge priority, which requires each group to
+    /// have exactly one badge.
+    pub fn priority_sum<T: Total>(&self) -> anyhow::Result<T> {
+        let priorities = self
+            .groups
+            .iter()
+            .map(|g| match g.badge() {
+                Some(badge) => Ok(T::from(badge.priority())),
+                None => bail!("{} has {} badges, expected exactly 1", g, g.common.len()),
+            })
+            .collect::<anyhow::Result<Vec<T>>>()?;
+        Ok(checked_sum(priorities)?)
+    }
+}
+
+/// A group's ID from the caller, if any, and its members.
+type Labelled = (Option<usize>, Vec<usize>);
+
+pub fn find_badges(rucksacks: &[Rucksack], search: &BadgeSearch) -> anyhow::Result<Badges> {
+    let size = search.group_size;
+    ensure!(size > 0, "groups need at least one rucksack");
+    let n = rucksacks.len();
+    let (members, leftovers): (Vec<Labelled>, Vec<usize>) = match &search.grouping {
+        Grouping::Chunks => {
+            let full = n - n % size;
+            let groups = (0..full)
+                .step_by(size)
+                .map(|i| (None, (i..i + size).collect()));
+            (groups.collect(), (full..n).collect())
+        }
+        Grouping::Window if n < size => (Vec::new(), (0..n).collect()),
+        Grouping::Window => (
+            (0..=n - size)
+                .map(|i| (None, (i..i + size).collect()))
+                .collect(),
+            Vec::new(),
+        ),
+        Grouping::Ids(ids) => {
+            ensure!(
+                ids.len() == n,
+                "got {} group IDs for {} rucksacks",
+                ids.len(),
+                n
+            );
+            let mut by_id: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for (i, &id) in ids.iter().enumerate() {
+                by_id.entry(id).or_default().push(i);
+            }
+            if let Some((id, m)) = by_id.iter().find(|(_, m)| m.len() > size) {
+                bail!(
+                    "group {} has {} rucksacks ({}), more than {}",
+                    id,
+                    m.len(),
+                    describe(m),
+                    size
+                );
+            }
+            let (full, partial): (Vec<_>, Vec<_>) =
+                by_id.into_iter().partition(|(_, m)| m.len() == size);
+            let full = full.into_iter().map(|(id, m)| (Some(id), m)).collect();
+            let mut leftovers: Vec<usize> = partial.into_iter().flat_map(|(_, m)| m).collect();
+            leftovers.sort_unstable();
+            (full, leftovers)
+        }
+    };
+    if search.leftovers == Leftovers::Reject && !leftovers.is_empty() {
+        bail!(
+            "rucksacks {} are not in a full group of {}",
+            describe(&leftovers),
+            size
+        );
+    }
+    let groups = members
+        .into_iter()
+        .enumerate()
+        .map(|(id, (label, members))| {
+            let common = members
+                .iter()
+                .fold(ItemSet::ALL, |acc, &i| acc & rucksacks[i].item_types());
+            Group {
+                id,
+                label,
+                members,
+                common,
+            }
+        })
+        .collect();
+    Ok(Badges { groups, leftovers })
+}
+
+/// Rucksack indices numbered from 1, as a range when they are consecutive.
+fn describe(indices: &[usize]) -> String {
+    match indices {
+        [first, .., last] if last - first + 1 == indices.len() => {
+            format!("{}-{}", first + 1, last + 1)
+        }
+        _ => indices
+            .iter()
+            .map(|i| (i + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day03::parse_input;
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+        jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+        PmmdzqPrVvPwwTWBwg
+        wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+        ttgJtRGJQctTZtZT
+        CrZsJsPPZsGzwwsLwLmpwMDw";
+
+    fn badges(found: &Badges) -> Vec<Option<char>> {
+        found
+            .groups
+            .iter()
+            .map(|g| g.badge().map(Item::letter))
+            .collect()
+    }
+
+    #[test]
+    fn chunks_test() -> anyhow::Result<()> {
+        let rucksacks = parse_input(EXAMPLE)?;
+        let found = find_badges(&rucksacks, &BadgeSearch::default())?;
+        assert_eq!(badges(&found), vec![Some('r'), Some('Z')]);
+        assert_eq!(found.groups[1].members, vec![3, 4, 5]);
+        assert_eq!(found.priority_sum::<i32>()?, 70);
+
+        let err = find_badges(&rucksacks[..5], &BadgeSearch::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "rucksacks 4-5 are not in a full group of 3"
+        );
+        let report = BadgeSearch {
+            leftovers: Leftovers::Report,
+            ..BadgeSearch::default()
+        };
+        let found = find_badges(&rucksacks[..5], &report)?;
+        assert_eq!((found.groups.len(), found.leftovers), (1, vec![3, 4]));
+
+        let pairs = BadgeSearch {
+            group_size: 2,
+            ..BadgeSearch::default()
+        };
+        let found = find_badges(&rucksacks, &pairs)?;
+        assert_eq!(found.groups.len(), 3);
+        let err = found.priority_sum::<i32>().unwrap_err();
+        assert!(err.to_string().starts_with("group 1 (rucksacks 1-2) has "));
+        Ok(())
+    }
+
+    #[test]
+    fn ids_test() -> anyhow::Result<()> {
+        let rucksacks = parse_input(EXAMPLE)?;
+        let search = BadgeSearch {
+            grouping: Grouping::Ids(vec![7, 9, 7, 9, 7, 9]),
+            ..BadgeSearch::default()
+        };
+        let found = find_badges(&rucksacks, &search)?;
+        assert_eq!(found.groups[0].members, vec![0, 2, 4]);
+        assert_eq!(found.groups[0].to_string(), "group 7 (rucksacks 1, 3, 5)");
+        assert_eq!((found.groups[1].id, found.groups[1].label), (1, Some(9)));
+
+        let search = BadgeSearch {
+            grouping: Grouping::Ids(vec![42, 42, 42, 1, 1, 1]),
+            ..BadgeSearch::default()
+        };
+        let found = find_badges(&rucksacks, &search)?;
+        assert_eq!(found.groups[1].to_string(), "group 42 (rucksacks 1-3)");
+        let search = BadgeSearch {
+            grouping: Grouping::Ids(vec![5, 5, 5, 5, 6, 6]),
+            leftovers: Leftovers::Report,
+            ..BadgeSearch::default()
+        };
+        let err = find_badges(&rucksacks, &search).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "group 5 has 4 rucksacks (1-4), more than 3"
+        );
+
+        let search = BadgeSearch {
+            grouping: Grouping::Ids(vec![0, 0, 0, 1, 1, 2]),
+            leftovers: Leftovers::Report,
+            ..BadgeSearch::default()
+        };
+        let found = find_badges(&rucksacks, &search)?;
+        assert_eq!(badges(&found), vec![Some('r')]);
+        assert_eq!(found.leftovers, vec![3, 4, 5]);
+        let search = BadgeSearch {
+            grouping: Grouping::Ids(vec![0]),
+            ..BadgeSearch::default()
+        };
+        assert!(find_badges(&rucksacks, &search).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn window_test() -> anyhow::Result<()> {
+        let rucksacks = parse_input(EXAMPLE)?;
+        let search = BadgeSearch {
+            grouping: Grouping::Window,
+            ..BadgeSearch::default()
+        };
+        let found = find_badges(&rucksacks, &search)?;
+        assert_eq!(found.groups.len(), 4);
+        assert_eq!(found.groups[3].members, vec![3, 4, 5]);
+        assert_eq!(badges(&found)[0], Some('r'));
+        assert_eq!(badges(&found)[3], Some('Z'));
+        Ok(())
+    }
+}
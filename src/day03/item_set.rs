@@ -0,0 +1,157 @@
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr, Sub},
+};
+
+use super::Item;
+
+/// A set of item types, stored as one bit per priority (1 through 52).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet(0);
+    pub const ALL: ItemSet = ItemSet(Self::MASK);
+
+    /// The set of item types in `items`, each written as a letter.
+    pub fn from_items(items: &str) -> anyhow::Result<Self> {
+        items
+            .chars()
+            .try_fold(ItemSet::EMPTY, |set, c| Ok(set.with(Item::try_from(c)?)))
+    }
+
+    /// Builds a set from raw bits; bit `p` is set for priority `p`.
+    pub fn from_bits(bits: u64) -> Self {
+        ItemSet(bits & Self::MASK)
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub fn with(self, item: Item) -> Self {
+        ItemSet(self.0 | 1 << item.priority())
+    }
+
+    pub fn contains(self, item: Item) -> bool {
+        self.0 & (1 << item.priority()) != 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        ItemSet(self.0 & !other.0)
+    }
+
+    /// The only item in the set, if it has exactly one.
+    pub fn single(self) -> Option<Item> {
+        match self.len() {
+            1 => self.iter().next(),
+            _ => None,
+        }
+    }
+
+    /// The items in the set, in increasing order of priority.
+    pub fn iter(self) -> Iter {
+        Iter(self.0)
+    }
+
+    const MASK: u64 = ((1 << 53) - 1) & !1;
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl Sub for ItemSet {
+    type Output = ItemSet;
+    fn sub(self, rhs: Self) -> Self {
+        self.difference(rhs)
+    }
+}
+
+impl FromIterator<Item> for ItemSet {
+    fn from_iter<I: IntoIterator<Item = Item>>(iter: I) -> Self {
+        iter.into_iter().fold(ItemSet::EMPTY, ItemSet::with)
+    }
+}
+
+pub struct Iter(u64);
+
+impl Iterator for Iter {
+    type Item = Item;
+    fn next(&mut self) -> Option<Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let p = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Item::from_priority(p)
+    }
+}
+
+impl IntoIterator for ItemSet {
+    type Item = Item;
+    type IntoIter = Iter;
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for ItemSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_operations_test() -> anyhow::Result<()> {
+        let a = ItemSet::from_items("vJrwpWtwJgWr")?;
+        let b = ItemSet::from_items("hcsFMMfFFhFp")?;
+        let p = Item::try_from('p')?;
+        assert_eq!((a & b).single(), Some(p));
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![p]);
+        assert_eq!((a | b).len(), a.len() + b.len() - 1);
+        assert!(!(a - b).contains(p));
+        assert_eq!((a - b).len(), a.len() - 1);
+        let priorities: Vec<u8> = ItemSet::from_items("Za")?
+            .iter()
+            .map(Item::priority)
+            .collect();
+        assert_eq!(priorities, vec![1, 52]);
+        assert_eq!([p, p].into_iter().collect::<ItemSet>().len(), 1);
+        assert!(ItemSet::EMPTY.is_empty());
+        assert_eq!(ItemSet::from_bits(u64::MAX), ItemSet::ALL);
+        assert_eq!(ItemSet::ALL.len(), 52);
+        assert!(ItemSet::from_items("a1").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use super::{Item, Rucksack};
+
+/// Exchanges the item at `first`, in the first compartment, with the item at
+/// `second`, in the second. Both are indices into the whole rucksack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Swap {
+    pub first: usize,
+    pub second: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub swaps: Vec<Swap>,
+    /// The rucksack after every swap, with each item type in one compartment.
+    pub fixed: Rucksack,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// A plan for each rucksack that can be fixed, by index.
+    pub plans: Vec<(usize, Plan)>,
+    /// Rucksacks whose item types can't be split into equal halves.
+    pub unfixable: Vec<usize>,
+}
+
+/// The fewest swaps that leave every item type in a single compartment, or
+/// `None` if no choice of types fills exactly half the rucksack.
+///
+/// Choosing which types go in the first compartment is a subset sum problem
+/// over the types' total counts; each type placed there costs as many swaps
+/// as it has items in the second compartment, so a knapsack-style dynamic
+/// program over (type, items placed) finds the cheapest choice.
+pub fn plan(rucksack: &Rucksack) -> Option<Plan> {
+    let items = rucksack.items();
+    let half = items.len() / 2;
+    let mut counts: BTreeMap<Item, (usize, usize)> = BTreeMap::new();
+    for (i, &item) in items.iter().enumerate() {
+        let entry = counts.entry(item).or_default();
+        if i < half {
+            entry.0 += 1;
+        } else {
+            entry.1 += 1;
+        }
+    }
+    let counts: Vec<(Item, usize, usize)> =
+        counts.into_iter().map(|(t, (a, b))| (t, a, b)).collect();
+
+    // best[i][s]: the fewest swaps that put exactly s items in the first
+    // compartment using only the first i types.
+    let mut best = vec![vec![None; half + 1]; counts.len() + 1];
+    best[0][0] = Some(0);
+    for (i, &(_, a, b)) in counts.iter().enumerate() {
+        for s in 0..=half {
+            let skip = best[i][s];
+            let take = s
+                .checked_sub(a + b)
+                .and_then(|prev| best[i][prev])
+                .map(|cost: usize| cost + b);
+            best[i + 1][s] = match (skip, take) {
+                (Some(x), Some(y)) => Some(x.min(y)),
+                (x, y) => x.or(y),
+            };
+        }
+    }
+    best[counts.len()][half]?;
+
+    let mut first = vec![false; counts.len()];
+    let mut s = half;
+    for i in (0..counts.len()).rev() {
+        let (_, a, b) = counts[i];
+        let here = best[i + 1][s];
+        if s >= a + b && best[i][s - a - b].map(|cost| cost + b) == here {
+            first[i] = true;
+            s -= a + b;
+        }
+    }
+    let in_first: BTreeMap<Item, bool> = counts.iter().map(|&(t, _, _)| t).zip(first).collect();
+
+    let leaving_first = (0..half).filter(|&i| !in_first[&items[i]]);
+    let leaving_second = (half..items.len()).filter(|&i| in_first[&items[i]]);
+    let swaps: Vec<Swap> = leaving_first
+        .zip(leaving_second)
+        .map(|(first, second)| Swap { first, second })
+        .collect();
+    let mut fixed = items.to_vec();
+    for swap in &swaps {
+        fixed.swap(swap.first, swap.second);
+    }
+    Some(Plan {
+        swaps,
+        fixed: Rucksack(fixed),
+    })
+}
+
+pub fn plan_all(rucksacks: &[Rucksack]) -> Report {
+    let mut report = Report {
+        plans: Vec::new(),
+        unfixable: Vec::new(),
+    };
+    for (i, r) in rucksacks.iter().enumerate() {
+        match plan(r) {
+            Some(p) => report.plans.push((i, p)),
+            None => report.unfixable.push(i),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day03::parse_input;
+
+    /// The fewest swaps, by trying every way to split the types.
+    fn brute_force(rucksack: &Rucksack) -> Option<usize> {
+        let counts = rucksack.item_counts();
+        let types: Vec<Item> = counts.keys().copied().collect();
+        let half = rucksack.items().len() / 2;
+        (0u64..1 << types.len())
+            .filter(|mask| {
+                let placed: usize = (0..types.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| counts[&types[i]])
+                    .sum();
+                placed == half
+            })
+            .map(|mask| {
+                rucksack.items()[half..]
+                    .iter()
+                    .filter(|item| {
+                        mask & (1 << types.iter().position(|t| t == *item).unwrap()) != 0
+                    })
+                    .count()
+            })
+            .min()
+    }
+
+    #[test]
+    fn plan_test() -> anyhow::Result<()> {
+        let rucksacks = parse_input("abba\naaab\nabab\naabbccdd\nvJrwpWtwJgWrhcsFMMfFFhFp")?;
+        let report = plan_all(&rucksacks);
+        assert_eq!(report.unfixable, vec![1]);
+        for (i, p) in &report.plans {
+            let original = &rucksacks[*i];
+            assert!(p.fixed.misplaced_items().is_empty());
+            assert_eq!(p.fixed.item_counts(), original.item_counts());
+            assert_eq!(Some(p.swaps.len()), brute_force(original));
+        }
+        let (_, first) = &report.plans[0];
+        assert_eq!(first.swaps.len(), 1);
+        assert!(["aabb", "bbaa"].contains(&first.fixed.to_string().as_str()));
+        let (_, sorted) = &report.plans[2];
+        assert_eq!(sorted.swaps, vec![]);
+        assert_eq!(sorted.fixed.to_string(), "aabbccdd");
+        Ok(())
+    }
+
+    #[test]
+    fn real_input_test() -> anyhow::Result<()> {
+        let input = std::fs::read_to_string("data/day03.input")?;
+        let rucksacks = parse_input(&input)?;
+        let report = plan_all(&rucksacks);
+        assert_eq!(report.plans.len() + report.unfixable.len(), rucksacks.len());
+        for (i, p) in &report.plans {
+            assert!(p.fixed.misplaced_items().is_empty());
+            assert_eq!(p.fixed.item_counts(), rucksacks[*i].item_counts());
+        }
+        Ok(())
+    }
+}
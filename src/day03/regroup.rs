@@ -0,0 +1,260 @@
+use super::{item_set::ItemSet, Item, Rucksack};
+
+/// Three rucksacks with exactly one item type in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Triple {
+    pub members: [usize; 3],
+    pub badge: Item,
+}
+
+/// How many partitions to look for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    First,
+    All,
+}
+
+/// Why no partition exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Proof {
+    /// The number of rucksacks isn't a multiple of three.
+    NotDivisible { count: usize },
+    /// This rucksack is not in any triple that shares exactly one item.
+    Unmatched { rucksack: usize },
+    /// Every combination of triples was tried; `nodes` counts the partial
+    /// partitions visited.
+    Exhausted { nodes: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Regrouping {
+    /// Each partition lists its triples ordered by their first member.
+    Found(Vec<Vec<Triple>>),
+    Impossible(Proof),
+}
+
+/// Splits rucksacks given in any order into groups of three that each share
+/// exactly one item type, like the groups in part 2.
+///
+/// Every candidate triple is found up front; then a backtracking search
+/// covers the rucksacks exactly once. Like dancing links, it keeps count of
+/// the triples still compatible with each rucksack, always branches on the
+/// ungrouped rucksack with the fewest, and backtracks as soon as one has none.
+pub fn regroup(rucksacks: &[Rucksack], mode: Mode) -> Regrouping {
+    let n = rucksacks.len();
+    if !n.is_multiple_of(3) {
+        return Regrouping::Impossible(Proof::NotDivisible { count: n });
+    }
+    let sets: Vec<ItemSet> = rucksacks.iter().map(Rucksack::item_types).collect();
+
+    let mut triples: Vec<Triple> = Vec::new();
+    // containing[i] holds the index of every triple with i as a member.
+    let mut containing: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let pair = sets[i] & sets[j];
+            if pair.is_empty() {
+                continue;
+            }
+            for (k, &set) in sets.iter().enumerate().skip(j + 1) {
+                if let Some(badge) = (pair & set).single() {
+                    for m in [i, j, k] {
+                        containing[m].push(triples.len());
+                    }
+                    triples.push(Triple {
+                        members: [i, j, k],
+                        badge,
+                    });
+                }
+            }
+        }
+    }
+    if let Some(rucksack) = containing.iter().position(Vec::is_empty) {
+        return Regrouping::Impossible(Proof::Unmatched { rucksack });
+    }
+
+    let mut search = Search {
+        live: containing.iter().map(Vec::len).collect(),
+        blocked: vec![0; triples.len()],
+        triples,
+        containing,
+        used: vec![false; n],
+        current: Vec::with_capacity(n / 3),
+        found: Vec::new(),
+        nodes: 0,
+        mode,
+    };
+    search.extend();
+    if search.found.is_empty() {
+        Regrouping::Impossible(Proof::Exhausted {
+            nodes: search.nodes,
+        })
+    } else {
+        Regrouping::Found(search.found)
+    }
+}
+
+struct Search {
+    triples: Vec<Triple>,
+    containing: Vec<Vec<usize>>,
+    /// How many members of each triple are already grouped; a triple is
+    /// compatible while this is zero.
+    blocked: Vec<u8>,
+    /// How many compatible triples each rucksack is in.
+    live: Vec<usize>,
+    used: Vec<bool>,
+    current: Vec<Triple>,
+    found: Vec<Vec<Triple>>,
+    nodes: usize,
+    mode: Mode,
+}
+
+impl Search {
+    /// Returns true once the search should stop.
+    fn extend(&mut self) -> bool {
+        self.nodes += 1;
+        let next = (0..self.used.len())
+            .filter(|&i| !self.used[i])
+            .min_by_key(|&i| self.live[i]);
+        let Some(next) = next else {
+            let mut partition = self.current.clone();
+            partition.sort_unstable_by_key(|t| t.members[0]);
+            self.found.push(partition);
+            return self.mode == Mode::First;
+        };
+        for c in 0..self.containing[next].len() {
+            let t = self.containing[next][c];
+            if self.blocked[t] != 0 {
+                continue;
+            }
+            let triple = self.triples[t];
+            self.cover(triple);
+            self.current.push(triple);
+            let done = self.extend();
+            self.current.pop();
+            self.uncover(triple);
+            if done {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn cover(&mut self, triple: Triple) {
+        for m in triple.members {
+            self.used[m] = true;
+            for &t in &self.containing[m] {
+                self.blocked[t] += 1;
+                if self.blocked[t] == 1 {
+                    for x in self.triples[t].members {
+                        self.live[x] -= 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn uncover(&mut self, triple: Triple) {
+        for m in triple.members.into_iter().rev() {
+            for &t in &self.containing[m] {
+                self.blocked[t] -= 1;
+                if self.blocked[t] == 0 {
+                    for x in self.triples[t].members {
+                        self.live[x] += 1;
+                    }
+                }
+            }
+            self.used[m] = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day03::parse_input;
+
+    fn check(rucksacks: &[Rucksack], partition: &[Triple]) {
+        let mut seen: Vec<usize> = partition.iter().flat_map(|t| t.members).collect();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..rucksacks.len()).collect::<Vec<_>>());
+        for t in partition {
+            let common = t
+                .members
+                .iter()
+                .fold(ItemSet::ALL, |acc, &m| acc & rucksacks[m].item_types());
+            assert_eq!(common.single(), Some(t.badge));
+        }
+    }
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        // The example's two groups, interleaved.
+        let rucksacks = parse_input(
+            "vJrwpWtwJgWrhcsFMMfFFhFp
+            wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+            jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+            ttgJtRGJQctTZtZT
+            PmmdzqPrVvPwwTWBwg
+            CrZsJsPPZsGzwwsLwLmpwMDw",
+        )?;
+        let Regrouping::Found(all) = regroup(&rucksacks, Mode::All) else {
+            panic!("expected a partition");
+        };
+        for partition in &all {
+            check(&rucksacks, partition);
+        }
+        let original = vec![
+            Triple {
+                members: [0, 2, 4],
+                badge: Item::try_from('r')?,
+            },
+            Triple {
+                members: [1, 3, 5],
+                badge: Item::try_from('Z')?,
+            },
+        ];
+        assert!(all.contains(&original));
+        let Regrouping::Found(first) = regroup(&rucksacks, Mode::First) else {
+            panic!("expected a partition");
+        };
+        assert_eq!(first.len(), 1);
+        assert!(all.contains(&first[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn impossible_test() -> anyhow::Result<()> {
+        let rucksacks = parse_input("ab\nac\nad\nxy\nxz\nwv")?;
+        assert_eq!(
+            regroup(&rucksacks[..4], Mode::First),
+            Regrouping::Impossible(Proof::NotDivisible { count: 4 })
+        );
+        assert_eq!(
+            regroup(&rucksacks, Mode::First),
+            Regrouping::Impossible(Proof::Unmatched { rucksack: 3 })
+        );
+        // Every rucksack is in some triple, but no two of them are disjoint.
+        let rucksacks = parse_input("ee\nce\nff\ncf\ncd\nef")?;
+        assert!(matches!(
+            regroup(&rucksacks, Mode::All),
+            Regrouping::Impossible(Proof::Exhausted { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn real_input_test() -> anyhow::Result<()> {
+        let input = std::fs::read_to_string("data/day03.input")?;
+        let rucksacks = parse_input(&input)?;
+        let n = rucksacks.len();
+        assert_eq!(n, 300);
+        // A fixed shuffle: 7 is coprime with 300.
+        let shuffled: Vec<Rucksack> = (0..n).map(|i| rucksacks[i * 7 % n].clone()).collect();
+        let Regrouping::Found(found) = regroup(&shuffled, Mode::First) else {
+            panic!("expected a partition");
+        };
+        check(&shuffled, &found[0]);
+        Ok(())
+    }
+}
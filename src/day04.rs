@@ -78,65 +78,160 @@ So, in this example, the number of overlapping assignment pairs is 4.
 In how many assignment pairs do the ranges overlap?
  */
 
-use std::ops::RangeInclusive;
+pub mod coverage;
+pub mod overlap;
+pub mod reassign;
 
-use anyhow::anyhow;
 use nom::{
     bytes::complete::tag,
-    character::complete::{digit1, multispace1},
-    combinator::all_consuming,
-    multi::separated_list1,
-    sequence::separated_pair,
-    IResult,
+    character::complete::digit1,
+    combinator::{cut, eof, map_res},
+    error::context,
+    multi::many0,
+    sequence::{preceded, separated_pair, terminated},
 };
 
+use crate::{
+    interval::Interval,
+    parse::{parse_lines, ParseResult},
+};
+
+/// A group of one or more elves, each assigned a range of sections.
 #[derive(Debug)]
-pub struct Assignment(RangeInclusive<i32>, RangeInclusive<i32>);
+pub struct Assignment(Vec<Interval>);
 
-pub fn parse_input(raw: &str) -> anyhow::Result<Vec<Assignment>> {
-    match all_consuming(separated_list1(multispace1, assignment_parser))(raw.trim()) {
-        Ok((_, lines)) => Ok(lines),
-        Err(e) => Err(anyhow!("could not parse input [{}]: {}", e, raw)),
+impl Assignment {
+    /// Each elf's sections.
+    pub fn members(&self) -> &[Interval] {
+        &self.0
+    }
+
+    /// Members sorted by first section, longest first among equal starts.
+    fn sorted(&self) -> Vec<Interval> {
+        let mut sorted = self.0.clone();
+        sorted.sort_unstable_by_key(|iv| (iv.lo(), std::cmp::Reverse(iv.hi())));
+        sorted
+    }
+
+    /// Whether some member's sections all belong to another member too.
+    pub fn any_contains(&self) -> bool {
+        // After sorting, a member is contained in an earlier one exactly when
+        // some earlier member reaches at least as far.
+        let sorted = self.sorted();
+        sorted
+            .iter()
+            .scan(i32::MIN, |reach, iv| {
+                let contained = *reach >= iv.hi();
+                *reach = (*reach).max(iv.hi());
+                Some(contained)
+            })
+            .skip(1)
+            .any(|contained| contained)
+    }
+
+    /// Whether any two members share a section.
+    pub fn any_overlap(&self) -> bool {
+        let sorted = self.sorted();
+        sorted
+            .iter()
+            .scan(i32::MIN, |reach, iv| {
+                let overlaps = *reach >= iv.lo();
+                *reach = (*reach).max(iv.hi());
+                Some(overlaps)
+            })
+            .skip(1)
+            .any(|overlaps| overlaps)
+    }
+
+    /// The sections every member is assigned to.
+    pub fn common(&self) -> Option<Interval> {
+        self.0[1..]
+            .iter()
+            .try_fold(self.0[0], |acc, &iv| acc.intersection(iv))
+    }
+
+    /// How many sections every member is assigned to.
+    pub fn common_len(&self) -> u64 {
+        self.common().map_or(0, Interval::len)
+    }
+
+    /// Indices of members whose sections are all covered by the other
+    /// members. Each is redundant on its own; two identical members are both
+    /// listed, though only one of them can be dropped.
+    pub fn redundant_members(&self) -> Vec<usize> {
+        (0..self.0.len())
+            .filter(|&i| {
+                let rest = self.0.iter().enumerate().filter(|&(j, _)| j != i);
+                rest.fold(vec![self.0[i]], |left, (_, &other)| {
+                    left.into_iter()
+                        .flat_map(|iv| iv.difference(other))
+                        .collect()
+                })
+                .is_empty()
+            })
+            .collect()
     }
 }
-fn assignment_parser(input: &str) -> IResult<&str, Assignment> {
-    let (input, (a, b)) = separated_pair(range_parser, tag(","), range_parser)(input)?;
-    Ok((input, Assignment(a, b)))
+
+pub fn parse_input(raw: &str) -> anyhow::Result<Vec<Assignment>> {
+    Ok(parse_lines(4, raw, assignment_parser)?)
+}
+fn assignment_parser(input: &str) -> ParseResult<'_, Assignment> {
+    let (input, first) = range_parser(input)?;
+    // Any number of further members may follow; once a ',' is seen, the
+    // range after it must parse, and anything else must end the line.
+    let (input, rest) = terminated(
+        many0(preceded(tag(","), cut(range_parser))),
+        context("','", eof),
+    )(input)?;
+    let mut members = Vec::with_capacity(rest.len() + 1);
+    members.push(first);
+    members.extend(rest);
+    Ok((input, Assignment(members)))
 }
-fn range_parser(input: &str) -> IResult<&str, RangeInclusive<i32>> {
-    let (input, (lo, hi)) = separated_pair(idx_parser, tag("-"), idx_parser)(input)?;
-    Ok((input, lo..=hi))
+fn range_parser(input: &str) -> ParseResult<'_, Interval> {
+    context(
+        "range with start <= end",
+        map_res(
+            separated_pair(idx_parser, context("'-'", tag("-")), idx_parser),
+            |(lo, hi)| Interval::new(lo, hi),
+        ),
+    )(input)
 }
-fn idx_parser(input: &str) -> IResult<&str, i32> {
-    let (input, ds) = digit1(input)?;
-    Ok((input, ds.parse().unwrap()))
+fn idx_parser(input: &str) -> ParseResult<'_, i32> {
+    context("section id", map_res(digit1, str::parse))(input)
 }
 
-pub fn solve1(assignments: &[Assignment]) -> usize {
-    assignments
-        .iter()
-        .filter(|&Assignment(a, b)| {
-            (a.contains(b.start()) && a.contains(b.end()))
-                || (b.contains(a.start()) && b.contains(a.end()))
-        })
-        .count()
+pub fn solve1(assignments: &[Assignment]) -> anyhow::Result<usize> {
+    Ok(assignments.iter().filter(|a| a.any_contains()).count())
 }
 
-pub fn solve2(assignments: &[Assignment]) -> usize {
-    assignments
-        .iter()
-        .filter(|&Assignment(a, b)| {
-            a.contains(b.start())
-                || a.contains(b.end())
-                || b.contains(a.start())
-                || b.contains(a.end())
-        })
-        .count()
+pub fn solve2(assignments: &[Assignment]) -> anyhow::Result<usize> {
+    Ok(assignments.iter().filter(|a| a.any_overlap()).count())
+}
+
+pub struct Day04;
+
+impl crate::Solution for Day04 {
+    type Input = Vec<Assignment>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(raw: &str) -> anyhow::Result<Self::Input> {
+        parse_input(raw)
+    }
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        solve1(input)
+    }
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        solve2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::parse::ParseError;
 
     #[test]
     fn example_test() -> anyhow::Result<()> {
@@ -149,7 +244,68 @@ mod test {
             2-6,4-8
         "#;
         let input = parse_input(input)?;
-        assert_eq!(solve1(&input), 2);
+        assert_eq!(solve1(&input)?, 2);
+        assert_eq!(solve2(&input)?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_line_test() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7;7-9\n";
+        let err = parse_input(input).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.snippet, "5-7;7-9");
+        assert_eq!(err.expected, "','");
+    }
+
+    #[test]
+    fn invalid_assignment_test() -> anyhow::Result<()> {
+        let err = parse_input("2-4,6-8\n1-99999999999,4-5").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "section id");
+
+        let err = parse_input("2-4,6-8\n4-5,8-3").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 5));
+        assert_eq!(err.expected, "range with start <= end");
+        Ok(())
+    }
+
+    #[test]
+    fn group_test() -> anyhow::Result<()> {
+        let input = parse_input("2-4,6-8,3-5\n1-9,2-3,8-8\n1-5,4-8,3-6\n5-5,5-5")?;
+        assert_eq!(input[0].members().len(), 3);
+        let contains: Vec<bool> = input.iter().map(Assignment::any_contains).collect();
+        assert_eq!(contains, vec![false, true, false, true]);
+        let overlap: Vec<bool> = input.iter().map(Assignment::any_overlap).collect();
+        assert_eq!(overlap, vec![true, true, true, true]);
+        let common: Vec<u64> = input.iter().map(Assignment::common_len).collect();
+        assert_eq!(common, vec![0, 0, 2, 1]);
+        assert_eq!(
+            input[2].common().map(|iv| iv.to_string()),
+            Some("4-5".to_owned())
+        );
+        assert_eq!(input[0].redundant_members(), Vec::<usize>::new());
+        assert_eq!(input[1].redundant_members(), vec![1, 2]);
+        // 3-6 is covered by 1-5 and 4-8 together, though by neither alone.
+        assert_eq!(input[2].redundant_members(), vec![2]);
+        assert_eq!(input[3].redundant_members(), vec![0, 1]);
+        assert_eq!(solve1(&input)?, 2);
+        assert_eq!(solve2(&input)?, 4);
+
+        // A lone elf is a group of one: nothing to contain, overlap or spare.
+        let single = parse_input("2-4")?;
+        assert_eq!(single[0].members().len(), 1);
+        assert!(!single[0].any_contains() && !single[0].any_overlap());
+        assert_eq!(single[0].common_len(), 3);
+        assert_eq!(single[0].redundant_members(), Vec::<usize>::new());
+        assert_eq!((solve1(&single)?, solve2(&single)?), (0, 0));
+
+        let err = parse_input("2-4,").unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((err.column, err.expected.as_str()), (5, "section id"));
         Ok(())
     }
 
@@ -157,7 +313,7 @@ mod test {
     fn part1() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day04.input")?;
         let input = parse_input(&input)?;
-        assert_eq!(solve1(&input), 475);
+        assert_eq!(solve1(&input)?, 475);
         Ok(())
     }
 
@@ -165,7 +321,7 @@ mod test {
     fn part2() -> anyhow::Result<()> {
         let input = std::fs::read_to_string("data/day04.input")?;
         let input = parse_input(&input)?;
-        assert_eq!(solve2(&input), 825);
+        assert_eq!(solve2(&input)?, 825);
         Ok(())
     }
 }
@@ -0,0 +1,156 @@
+use super::Assignment;
+use crate::interval::Interval;
+
+/// A run of sections that the same number of elves are assigned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub interval: Interval,
+    pub elves: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// Covered sections in increasing order; neighbouring segments always
+    /// differ in multiplicity or have a gap between them.
+    pub segments: Vec<Segment>,
+    /// Gaps between the lowest and highest covered sections.
+    pub uncovered: Vec<Interval>,
+    /// Sections covered by at least one elf.
+    pub covered: u64,
+    /// Section visits beyond the first: a section cleaned by three elves adds
+    /// two.
+    pub redundant: u64,
+}
+
+impl Coverage {
+    /// How many elves are assigned to `section`.
+    pub fn elves_at(&self, section: i32) -> usize {
+        let i = self.segments.partition_point(|s| s.interval.hi() < section);
+        match self.segments.get(i) {
+            Some(s) if s.interval.contains(section) => s.elves,
+            _ => 0,
+        }
+    }
+}
+
+/// Coverage of every elf's sections across the whole camp.
+pub fn camp_coverage(assignments: &[Assignment]) -> Coverage {
+    coverage(assignments.iter().flat_map(|a| a.members().iter().copied()))
+}
+
+/// Sweeps over the start and end of every interval in order, tracking how
+/// many are open in between.
+pub fn coverage(intervals: impl IntoIterator<Item = Interval>) -> Coverage {
+    // Positions are widened so that the event after i32::MAX still fits.
+    let mut events: Vec<(i64, isize)> = intervals
+        .into_iter()
+        .flat_map(|iv| [(iv.lo() as i64, 1), (iv.hi() as i64 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut result = Coverage {
+        segments: Vec::new(),
+        uncovered: Vec::new(),
+        covered: 0,
+        redundant: 0,
+    };
+    let mut open = 0isize;
+    let mut i = 0;
+    while i < events.len() {
+        let pos = events[i].0;
+        while i < events.len() && events[i].0 == pos {
+            open += events[i].1;
+            i += 1;
+        }
+        let Some(&(next, _)) = events.get(i) else {
+            break;
+        };
+        let run = Interval::new(pos as i32, (next - 1) as i32).expect("events are sorted");
+        if open == 0 {
+            result.uncovered.push(run);
+            continue;
+        }
+        let elves = open as usize;
+        result.covered += run.len();
+        result.redundant += run.len() * (elves as u64 - 1);
+        match result.segments.last_mut() {
+            Some(last) if last.elves == elves && last.interval.hi() as i64 + 1 == pos => {
+                last.interval = last.interval.union(run).expect("segments are adjacent");
+            }
+            _ => result.segments.push(Segment {
+                interval: run,
+                elves,
+            }),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day04::parse_input;
+
+    fn iv(lo: i32, hi: i32) -> Interval {
+        Interval::new(lo, hi).unwrap()
+    }
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        let input = parse_input("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8")?;
+        let cov = camp_coverage(&input);
+        let segments: Vec<(String, usize)> = cov
+            .segments
+            .iter()
+            .map(|s| (s.interval.to_string(), s.elves))
+            .collect();
+        let expected = [
+            ("2-2", 4),
+            ("3-3", 5),
+            ("4-5", 7),
+            ("6-6", 8),
+            ("7-7", 6),
+            ("8-8", 4),
+            ("9-9", 1),
+        ];
+        assert_eq!(segments, expected.map(|(s, n)| (s.to_owned(), n)).to_vec());
+        assert!(cov.uncovered.is_empty());
+        assert_eq!(cov.covered, 8);
+        // Every section visit minus one per covered section.
+        let visits: u64 = input
+            .iter()
+            .flat_map(|a| a.members())
+            .map(|iv| iv.len())
+            .sum();
+        assert_eq!(cov.redundant, visits - cov.covered);
+        assert_eq!(cov.elves_at(5), 7);
+        assert_eq!(cov.elves_at(1), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_test() {
+        let cov = coverage([iv(1, 3), iv(4, 5), iv(8, 9), iv(8, 8)]);
+        assert_eq!(
+            cov.segments,
+            vec![
+                Segment {
+                    interval: iv(1, 5),
+                    elves: 1
+                },
+                Segment {
+                    interval: iv(8, 8),
+                    elves: 2
+                },
+                Segment {
+                    interval: iv(9, 9),
+                    elves: 1
+                },
+            ]
+        );
+        assert_eq!(cov.uncovered, vec![iv(6, 7)]);
+        assert_eq!((cov.covered, cov.redundant), (7, 1));
+        assert_eq!(coverage([]).segments, vec![]);
+        assert_eq!(coverage([iv(i32::MAX, i32::MAX)]).covered, 1);
+    }
+}
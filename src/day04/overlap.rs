@@ -0,0 +1,214 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use super::Assignment;
+use crate::interval::Interval;
+
+/// One elf: the line of the input they appear on, and their position on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Elf {
+    pub assignment: usize,
+    pub member: usize,
+}
+
+/// Every elf's sections, sorted by where they start, with a segment tree of
+/// the furthest section reached by each block of them.
+#[derive(Debug, Clone)]
+pub struct OverlapIndex {
+    elves: Vec<(Interval, Elf)>,
+    max_hi: Vec<i32>,
+}
+
+impl OverlapIndex {
+    pub fn new(assignments: &[Assignment]) -> Self {
+        OverlapIndex::from_elves(assignments.iter().enumerate().flat_map(|(a, asg)| {
+            asg.members().iter().enumerate().map(move |(m, &iv)| {
+                (
+                    iv,
+                    Elf {
+                        assignment: a,
+                        member: m,
+                    },
+                )
+            })
+        }))
+    }
+
+    pub fn from_elves(elves: impl IntoIterator<Item = (Interval, Elf)>) -> Self {
+        let mut elves: Vec<(Interval, Elf)> = elves.into_iter().collect();
+        elves.sort_unstable();
+        let mut index = OverlapIndex {
+            max_hi: vec![i32::MIN; 4 * elves.len().max(1)],
+            elves,
+        };
+        if !index.elves.is_empty() {
+            index.build(1, 0, index.elves.len());
+        }
+        index
+    }
+
+    fn build(&mut self, node: usize, l: usize, r: usize) -> i32 {
+        let hi = if r - l == 1 {
+            self.elves[l].0.hi()
+        } else {
+            let mid = (l + r) / 2;
+            self.build(2 * node, l, mid)
+                .max(self.build(2 * node + 1, mid, r))
+        };
+        self.max_hi[node] = hi;
+        hi
+    }
+
+    pub fn len(&self) -> usize {
+        self.elves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    /// Every elf assigned at least one section in `range`, in order of their
+    /// first section.
+    ///
+    /// Only elves starting no later than `range.hi()` can overlap, and of
+    /// those the tree skips any block that ends before `range.lo()`, so this
+    /// takes O((k + 1) log n) time for k results.
+    pub fn overlapping(&self, range: Interval) -> Vec<Elf> {
+        let end = self.elves.partition_point(|(iv, _)| iv.lo() <= range.hi());
+        let mut acc = Vec::new();
+        if end > 0 {
+            self.collect(1, 0, self.elves.len(), end, range.lo(), &mut acc);
+        }
+        acc
+    }
+
+    fn collect(&self, node: usize, l: usize, r: usize, end: usize, lo: i32, acc: &mut Vec<Elf>) {
+        if l >= end || self.max_hi[node] < lo {
+            return;
+        }
+        if r - l == 1 {
+            acc.push(self.elves[l].1);
+            return;
+        }
+        let mid = (l + r) / 2;
+        self.collect(2 * node, l, mid, end, lo, acc);
+        self.collect(2 * node + 1, mid, r, end, lo, acc);
+    }
+
+    /// Every pair of elves with a section in common, each pair once with the
+    /// lower `Elf` first.
+    ///
+    /// Sweeps through the elves by first section, keeping a heap of those
+    /// still in progress ordered by last section. Once the ones that ended
+    /// earlier are popped, everyone left overlaps the newcomer, so this takes
+    /// O(n log n + k) time for k pairs.
+    pub fn overlapping_pairs(&self) -> Vec<(Elf, Elf)> {
+        let mut active: BinaryHeap<Reverse<(i32, Elf)>> = BinaryHeap::new();
+        let mut acc = Vec::new();
+        for &(iv, elf) in &self.elves {
+            while matches!(active.peek(), Some(Reverse((hi, _))) if *hi < iv.lo()) {
+                active.pop();
+            }
+            for Reverse((_, other)) in active.iter() {
+                acc.push((elf.min(*other), elf.max(*other)));
+            }
+            active.push(Reverse((iv.hi(), elf)));
+        }
+        acc
+    }
+
+    /// Overlapping pairs of elves from different lines of the input.
+    pub fn cross_assignment_pairs(&self) -> Vec<(Elf, Elf)> {
+        let mut pairs = self.overlapping_pairs();
+        pairs.retain(|(a, b)| a.assignment != b.assignment);
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day04::parse_input;
+
+    fn all_elves(assignments: &[Assignment]) -> Vec<(Interval, Elf)> {
+        let mut acc = Vec::new();
+        for (a, asg) in assignments.iter().enumerate() {
+            for (m, &iv) in asg.members().iter().enumerate() {
+                acc.push((
+                    iv,
+                    Elf {
+                        assignment: a,
+                        member: m,
+                    },
+                ));
+            }
+        }
+        acc
+    }
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        let input = parse_input("2-4,6-8\n2-3,4-5\n5-7,7-9")?;
+        let index = OverlapIndex::new(&input);
+        assert_eq!(index.len(), 6);
+        let elf = |assignment, member| Elf { assignment, member };
+        let mut found = index.overlapping(Interval::new(8, 10)?);
+        found.sort();
+        assert_eq!(found, vec![elf(0, 1), elf(2, 1)]);
+        assert!(index.overlapping(Interval::new(10, 12)?).is_empty());
+        assert!(index.overlapping(Interval::new(0, 1)?).is_empty());
+
+        let mut pairs = index.cross_assignment_pairs();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                (elf(0, 0), elf(1, 0)),
+                (elf(0, 0), elf(1, 1)),
+                (elf(0, 1), elf(2, 0)),
+                (elf(0, 1), elf(2, 1)),
+                (elf(1, 1), elf(2, 0)),
+            ]
+        );
+        assert_eq!(index.overlapping_pairs().len(), pairs.len() + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn brute_force_test() -> anyhow::Result<()> {
+        let input = std::fs::read_to_string("data/day04.input")?;
+        let input = &parse_input(&input)?[..300];
+        let elves = all_elves(input);
+        let index = OverlapIndex::new(input);
+
+        let mut expected = Vec::new();
+        for (i, &(a, ea)) in elves.iter().enumerate() {
+            for &(b, eb) in &elves[i + 1..] {
+                if a.overlaps(b) {
+                    expected.push((ea.min(eb), ea.max(eb)));
+                }
+            }
+        }
+        expected.sort();
+        let mut pairs = index.overlapping_pairs();
+        pairs.sort();
+        assert_eq!(pairs, expected);
+
+        for (lo, hi) in [(1, 1), (10, 20), (50, 99), (90, 90)] {
+            let range = Interval::new(lo, hi)?;
+            let mut expected: Vec<Elf> = elves
+                .iter()
+                .filter(|(iv, _)| iv.overlaps(range))
+                .map(|&(_, e)| e)
+                .collect();
+            expected.sort();
+            let mut found = index.overlapping(range);
+            found.sort();
+            assert_eq!(found, expected);
+        }
+        let everything = Interval::new(i32::MIN, i32::MAX)?;
+        assert!(OverlapIndex::from_elves([])
+            .overlapping(everything)
+            .is_empty());
+        Ok(())
+    }
+}
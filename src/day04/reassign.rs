@@ -0,0 +1,236 @@
+use std::{cmp::Reverse, fmt};
+
+use super::{overlap::Elf, Assignment};
+use crate::interval::Interval;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    pub elf: Elf,
+    pub before: Interval,
+    /// `None` if the elf is no longer needed.
+    pub after: Option<Interval>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "assignment {} elf {}: {} -> ",
+            self.elf.assignment + 1,
+            self.elf.member + 1,
+            self.before
+        )?;
+        match self.after {
+            Some(after) => write!(f, "{}", after),
+            None => write!(f, "nothing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reassignment {
+    /// Each elf's new sections, laid out like the input.
+    pub assignments: Vec<Vec<Option<Interval>>>,
+    /// Every elf whose sections changed, in input order.
+    pub changes: Vec<Change>,
+}
+
+impl fmt::Display for Reassignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        write!(
+            f,
+            "{} of {} elves reassigned",
+            self.changes.len(),
+            self.assignments.iter().map(Vec::len).sum::<usize>()
+        )
+    }
+}
+
+/// New assignments in which no two elves share a section and the same
+/// sections are covered as before, changing the old ranges as little as
+/// possible and then keeping section counts as even as possible.
+///
+/// Elves whose ranges overlap nobody else's keep them. The rest are grouped
+/// into clusters of ranges that overlap one another, and each cluster is
+/// handled on its own: an elf whose range lies within an earlier one's is
+/// redundant and gets nothing, and the others, in the order their ranges
+/// start, split the cluster's sections between them. Where two neighbours
+/// overlap, the cut between them goes anywhere in the shared sections, which
+/// moves their endpoints by the same total, so it is placed as close to an
+/// even split of the cluster as the shared sections allow.
+pub fn reassign(assignments: &[Assignment]) -> Reassignment {
+    let mut elves: Vec<(Interval, Elf)> = Vec::new();
+    for (a, asg) in assignments.iter().enumerate() {
+        for (m, &iv) in asg.members().iter().enumerate() {
+            let elf = Elf {
+                assignment: a,
+                member: m,
+            };
+            elves.push((iv, elf));
+        }
+    }
+    // Among ranges starting together the longest comes first, so any range
+    // within another comes after it.
+    elves.sort_unstable_by_key(|&(iv, elf)| (iv.lo(), Reverse(iv.hi()), elf));
+
+    let mut result: Vec<Vec<Option<Interval>>> = assignments
+        .iter()
+        .map(|a| vec![None; a.members().len()])
+        .collect();
+    let mut start = 0;
+    while start < elves.len() {
+        let mut reach = elves[start].0.hi();
+        let mut end = start + 1;
+        while let Some(&(iv, _)) = elves.get(end).filter(|(iv, _)| iv.lo() <= reach) {
+            reach = reach.max(iv.hi());
+            end += 1;
+        }
+        let mut kept: Vec<(Interval, Elf)> = Vec::new();
+        for &(iv, elf) in &elves[start..end] {
+            match kept.last() {
+                Some(&(last, _)) if iv.hi() <= last.hi() => {}
+                _ => kept.push((iv, elf)),
+            }
+        }
+        let old: Vec<Interval> = kept.iter().map(|&(iv, _)| iv).collect();
+        for (&(_, elf), piece) in kept.iter().zip(split(&old)) {
+            result[elf.assignment][elf.member] = Some(piece);
+        }
+        start = end;
+    }
+
+    let mut changes = Vec::new();
+    for (a, asg) in assignments.iter().enumerate() {
+        for (m, &before) in asg.members().iter().enumerate() {
+            let after = result[a][m];
+            if after != Some(before) {
+                changes.push(Change {
+                    elf: Elf {
+                        assignment: a,
+                        member: m,
+                    },
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+    Reassignment {
+        assignments: result,
+        changes,
+    }
+}
+
+/// Cuts the sections covered by `old` into one piece per range. The ranges
+/// must start and end in strictly increasing order, each overlapping the
+/// next.
+///
+/// The cut before range i may go anywhere from its start to just past the
+/// end of range i - 1; clamping the even split into that window keeps the
+/// cuts in strictly increasing order, so no piece is empty.
+fn split(old: &[Interval]) -> Vec<Interval> {
+    let (lo, hi) = (old[0].lo() as i64, old[old.len() - 1].hi() as i64);
+    let (total, r) = ((hi - lo + 1) as u64, old.len() as u64);
+    let mut cuts = vec![lo];
+    for i in 1..old.len() {
+        let even = lo + (i as u64 * total / r) as i64;
+        cuts.push(even.clamp(old[i].lo() as i64, old[i - 1].hi() as i64 + 1));
+    }
+    cuts.push(hi + 1);
+    cuts.windows(2)
+        .map(|w| Interval::new(w[0] as i32, (w[1] - 1) as i32).expect("cuts are increasing"))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::day04::{coverage::coverage, parse_input};
+
+    fn check(assignments: &[Assignment], result: &Reassignment) {
+        let old = coverage(assignments.iter().flat_map(|a| a.members().iter().copied()));
+        let new: Vec<Interval> = result
+            .assignments
+            .iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .collect();
+        let cov = coverage(new.iter().copied());
+        assert_eq!(cov.redundant, 0);
+        assert_eq!(cov.covered, old.covered);
+        assert_eq!(cov.uncovered, old.uncovered);
+    }
+
+    #[test]
+    fn example_test() -> anyhow::Result<()> {
+        let input = parse_input("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8")?;
+        let result = reassign(&input);
+        check(&input, &result);
+        // 2-8 and 7-9 cover everything the other ten elves were doing.
+        let kept: Vec<Interval> = result
+            .assignments
+            .iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .collect();
+        assert_eq!(kept, vec![Interval::new(7, 9)?, Interval::new(2, 6)?]);
+        assert_eq!(result.changes.len(), 11);
+
+        let input = parse_input("1-3,4-6\n10-20,12-13")?;
+        let result = reassign(&input);
+        check(&input, &result);
+        assert_eq!(
+            result.assignments[0],
+            vec![Some(Interval::new(1, 3)?), Some(Interval::new(4, 6)?)]
+        );
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(
+            result.changes[0].to_string(),
+            "assignment 2 elf 2: 12-13 -> nothing"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn minimal_change_test() -> anyhow::Result<()> {
+        // Ranges that only touch are left alone.
+        let input = parse_input("1-2,3-4\n5-50,51-51")?;
+        let result = reassign(&input);
+        assert!(result.changes.is_empty());
+        assert_eq!(result.to_string(), "0 of 4 elves reassigned");
+
+        // A one-section overlap costs one section.
+        let input = parse_input("1-100,100-101")?;
+        let result = reassign(&input);
+        check(&input, &result);
+        assert_eq!(result.changes.len(), 1);
+        assert_eq!(
+            result.changes[0].to_string(),
+            "assignment 1 elf 1: 1-100 -> 1-99"
+        );
+
+        // A wider overlap is split near the middle of the cluster.
+        let input = parse_input("1-10,5-15")?;
+        let result = reassign(&input);
+        check(&input, &result);
+        assert_eq!(
+            result.assignments[0],
+            vec![Some(Interval::new(1, 7)?), Some(Interval::new(8, 15)?)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn real_input_test() -> anyhow::Result<()> {
+        let input = std::fs::read_to_string("data/day04.input")?;
+        let input = parse_input(&input)?;
+        let result = reassign(&input);
+        check(&input, &result);
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+};
+
+/// A non-empty, inclusive range of integers `lo..=hi`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    lo: i32,
+    hi: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReversedInterval {
+    pub lo: i32,
+    pub hi: i32,
+}
+
+impl Display for ReversedInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "reversed range {}-{}", self.lo, self.hi)
+    }
+}
+
+impl std::error::Error for ReversedInterval {}
+
+/// How two intervals are positioned relative to each other, following Allen's
+/// interval algebra. Exactly one relation holds for any pair.
+///
+/// Since intervals here are sets of integers, `Meets` means the second starts
+/// right after the first ends, with no gap between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    FinishedBy,
+    Contains,
+    StartedBy,
+    OverlappedBy,
+    MetBy,
+    After,
+}
+
+impl Relation {
+    /// The relation that holds with the intervals swapped.
+    pub fn inverse(self) -> Relation {
+        use Relation::*;
+        match self {
+            Before => After,
+            Meets => MetBy,
+            Overlaps => OverlappedBy,
+            Starts => StartedBy,
+            During => Contains,
+            Finishes => FinishedBy,
+            Equals => Equals,
+            FinishedBy => Finishes,
+            Contains => During,
+            StartedBy => Starts,
+            OverlappedBy => Overlaps,
+            MetBy => Meets,
+            After => Before,
+        }
+    }
+}
+
+impl Interval {
+    pub fn new(lo: i32, hi: i32) -> Result<Self, ReversedInterval> {
+        if lo > hi {
+            return Err(ReversedInterval { lo, hi });
+        }
+        Ok(Interval { lo, hi })
+    }
+
+    pub fn point(x: i32) -> Self {
+        Interval { lo: x, hi: x }
+    }
+
+    pub fn lo(self) -> i32 {
+        self.lo
+    }
+
+    pub fn hi(self) -> i32 {
+        self.hi
+    }
+
+    /// How many integers the interval holds; never zero.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(self) -> u64 {
+        (self.hi as i64 - self.lo as i64 + 1) as u64
+    }
+
+    pub fn contains(self, x: i32) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    /// Whether every integer in `other` is also in `self`.
+    pub fn contains_interval(self, other: Interval) -> bool {
+        self.lo <= other.lo && other.hi <= self.hi
+    }
+
+    pub fn overlaps(self, other: Interval) -> bool {
+        self.lo <= other.hi && other.lo <= self.hi
+    }
+
+    pub fn intersection(self, other: Interval) -> Option<Interval> {
+        Interval::new(self.lo.max(other.lo), self.hi.min(other.hi)).ok()
+    }
+
+    /// The two intervals as one, if they overlap or are adjacent.
+    pub fn union(self, other: Interval) -> Option<Interval> {
+        let touching = self.overlaps(other)
+            || self.hi.checked_add(1) == Some(other.lo)
+            || other.hi.checked_add(1) == Some(self.lo);
+        touching.then(|| Interval {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        })
+    }
+
+    /// The parts of `self` not in `other`: none, one or two intervals, in
+    /// increasing order.
+    pub fn difference(self, other: Interval) -> Vec<Interval> {
+        if !self.overlaps(other) {
+            return vec![self];
+        }
+        let mut acc = Vec::with_capacity(2);
+        if self.lo < other.lo {
+            acc.push(Interval {
+                lo: self.lo,
+                hi: other.lo - 1,
+            });
+        }
+        if other.hi < self.hi {
+            acc.push(Interval {
+                lo: other.hi + 1,
+                hi: self.hi,
+            });
+        }
+        acc
+    }
+
+    pub fn relation(self, other: Interval) -> Relation {
+        use Relation::*;
+        if self.hi < other.lo {
+            return if self.hi + 1 == other.lo {
+                Meets
+            } else {
+                Before
+            };
+        }
+        if other.hi < self.lo {
+            return if other.hi + 1 == self.lo {
+                MetBy
+            } else {
+                After
+            };
+        }
+        match (self.lo.cmp(&other.lo), self.hi.cmp(&other.hi)) {
+            (Ordering::Equal, Ordering::Equal) => Equals,
+            (Ordering::Equal, Ordering::Less) => Starts,
+            (Ordering::Equal, Ordering::Greater) => StartedBy,
+            (Ordering::Greater, Ordering::Equal) => Finishes,
+            (Ordering::Less, Ordering::Equal) => FinishedBy,
+            (Ordering::Greater, Ordering::Less) => During,
+            (Ordering::Less, Ordering::Greater) => Contains,
+            (Ordering::Less, Ordering::Less) => Overlaps,
+            (Ordering::Greater, Ordering::Greater) => OverlappedBy,
+        }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.lo, self.hi)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn iv(lo: i32, hi: i32) -> Interval {
+        Interval::new(lo, hi).unwrap()
+    }
+
+    #[test]
+    fn algebra_test() {
+        assert_eq!(
+            Interval::new(8, 3).unwrap_err().to_string(),
+            "reversed range 8-3"
+        );
+        assert_eq!(iv(2, 8).len(), 7);
+        assert_eq!(iv(i32::MIN, i32::MAX).len(), 1 << 32);
+        assert!(iv(2, 8).contains_interval(iv(3, 7)));
+        assert!(!iv(3, 7).contains_interval(iv(2, 8)));
+        assert!(iv(5, 7).overlaps(iv(7, 9)));
+        assert!(!iv(2, 4).overlaps(iv(6, 8)));
+        assert_eq!(iv(5, 7).intersection(iv(7, 9)), Some(iv(7, 7)));
+        assert_eq!(iv(2, 4).intersection(iv(6, 8)), None);
+        assert_eq!(iv(2, 4).union(iv(5, 8)), Some(iv(2, 8)));
+        assert_eq!(iv(2, 4).union(iv(6, 8)), None);
+        assert_eq!(iv(2, 8).difference(iv(4, 5)), vec![iv(2, 3), iv(6, 8)]);
+        assert_eq!(iv(2, 8).difference(iv(1, 9)), vec![]);
+        assert_eq!(iv(2, 8).difference(iv(6, 9)), vec![iv(2, 5)]);
+        assert_eq!(iv(2, 4).to_string(), "2-4");
+    }
+
+    #[test]
+    fn relation_test() {
+        use Relation::*;
+        let cases = [
+            (iv(1, 2), iv(4, 5), Before),
+            (iv(1, 3), iv(4, 5), Meets),
+            (iv(1, 4), iv(3, 5), Overlaps),
+            (iv(3, 4), iv(3, 5), Starts),
+            (iv(4, 4), iv(3, 5), During),
+            (iv(4, 5), iv(3, 5), Finishes),
+            (iv(3, 5), iv(3, 5), Equals),
+        ];
+        for (a, b, relation) in cases {
+            assert_eq!(a.relation(b), relation);
+            assert_eq!(b.relation(a), relation.inverse());
+        }
+    }
+}
@@ -1,4 +1,86 @@
+use std::fmt::Display;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
+pub mod interval;
+pub mod num;
+pub mod parse;
+
+/// A single day's puzzle: parse the raw input once, then answer both parts.
+pub trait Solution {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(raw: &str) -> anyhow::Result<Self::Input>;
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Answer1>;
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Answer2>;
+}
+
+/// A parsed input whose concrete type has been erased, so that every day can
+/// be driven through the same interface.
+pub trait Prepared {
+    fn part1(&self) -> anyhow::Result<String>;
+    fn part2(&self) -> anyhow::Result<String>;
+}
+
+struct Parsed<S: Solution>(S::Input);
+
+impl<S: Solution> Prepared for Parsed<S> {
+    fn part1(&self) -> anyhow::Result<String> {
+        Ok(S::part1(&self.0)?.to_string())
+    }
+    fn part2(&self) -> anyhow::Result<String> {
+        Ok(S::part2(&self.0)?.to_string())
+    }
+}
+
+fn prepare<S: Solution + 'static>(raw: &str) -> anyhow::Result<Box<dyn Prepared>> {
+    Ok(Box::new(Parsed::<S>(S::parse(raw)?)))
+}
+
+pub struct Day {
+    pub day: u32,
+    pub prepare: fn(&str) -> anyhow::Result<Box<dyn Prepared>>,
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        prepare: prepare::<day01::Day01>,
+    },
+    Day {
+        day: 2,
+        prepare: prepare::<day02::Day02>,
+    },
+    Day {
+        day: 3,
+        prepare: prepare::<day03::Day03>,
+    },
+    Day {
+        day: 4,
+        prepare: prepare::<day04::Day04>,
+    },
+];
+
+pub fn lookup(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry_test() -> anyhow::Result<()> {
+        let day = lookup(4).ok_or_else(|| anyhow::anyhow!("day 4 is not registered"))?;
+        let input = std::fs::read_to_string("data/day04.input")?;
+        let prepared = (day.prepare)(&input)?;
+        assert_eq!(prepared.part1()?, "475");
+        assert_eq!(prepared.part2()?, "825");
+        assert!(lookup(25).is_none());
+        Ok(())
+    }
+}
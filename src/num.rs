@@ -0,0 +1,62 @@
+use std::fmt::{self, Debug, Display};
+
+/// An integer type that puzzle answers can be accumulated into.
+pub trait Total: Copy + Ord + Debug + Display + From<u8> {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_total {
+    ($($t:ty),*) => {
+        $(
+            impl Total for $t {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_total!(i32, i64, i128, u32, u64, u128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    pub type_name: &'static str,
+}
+
+impl Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "total does not fit in {}", self.type_name)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+/// Sums `xs`, failing instead of wrapping if the total does not fit in `T`.
+pub fn checked_sum<T: Total>(xs: impl IntoIterator<Item = T>) -> Result<T, OverflowError> {
+    xs.into_iter().try_fold(T::from(0), |acc, x| {
+        acc.checked_add(x).ok_or(OverflowError {
+            type_name: std::any::type_name::<T>(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checked_sum_test() {
+        assert_eq!(checked_sum([1u64, 2, 3]), Ok(6));
+        assert_eq!(checked_sum(Vec::<i32>::new()), Ok(0));
+        assert_eq!(
+            checked_sum([i32::MAX, 1]),
+            Err(OverflowError { type_name: "i32" })
+        );
+        assert_eq!(
+            checked_sum([u128::MAX, 0]),
+            Ok(u128::MAX),
+            "no overflow unless the total actually exceeds the type"
+        );
+    }
+}
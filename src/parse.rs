@@ -0,0 +1,131 @@
+use std::fmt;
+
+use nom::{
+    combinator::all_consuming,
+    error::{VerboseError, VerboseErrorKind},
+    Finish, IResult, Offset,
+};
+
+pub type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// Where and why a puzzle input failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    /// 1-based line number within the raw input.
+    pub line: usize,
+    /// 1-based column (in characters) within that line.
+    pub column: usize,
+    /// The full text of the offending line.
+    pub snippet: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Builds an error pointing at byte `offset` of `raw`.
+    pub fn at(day: u32, raw: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let offset = offset.min(raw.len());
+        let line_start = raw[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = raw[offset..].find('\n').map_or(raw.len(), |i| offset + i);
+        ParseError {
+            day,
+            line: raw[..line_start].matches('\n').count() + 1,
+            column: raw[line_start..offset].chars().count() + 1,
+            snippet: raw[line_start..line_end].trim_end_matches('\r').to_owned(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Locates a nom failure within `raw`, which must be the string (or a
+    /// superstring of the slice) that the failing parser was run on.
+    pub fn from_nom(day: u32, raw: &str, err: VerboseError<&str>) -> Self {
+        let Some(&(remaining, ref kind)) = err.errors.first() else {
+            return ParseError::at(day, raw, 0, "valid input");
+        };
+        let expected = err
+            .errors
+            .iter()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(ctx) => Some(ctx.to_string()),
+                _ => None,
+            })
+            .unwrap_or_else(|| match kind {
+                VerboseErrorKind::Char(c) => format!("'{}'", c),
+                VerboseErrorKind::Nom(nom::error::ErrorKind::Eof) => "end of line".to_owned(),
+                VerboseErrorKind::Nom(kind) => kind.description().to_lowercase(),
+                VerboseErrorKind::Context(ctx) => ctx.to_string(),
+            });
+        ParseError::at(day, raw, raw.offset(remaining), expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(
+            f,
+            "day {:02}: line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Runs `parser` over every non-blank line of `raw`, ignoring surrounding
+/// whitespace, and requires at least one line to be present.
+pub fn parse_lines<'a, T>(
+    day: u32,
+    raw: &'a str,
+    mut parser: impl FnMut(&'a str) -> ParseResult<'a, T>,
+) -> Result<Vec<T>, ParseError> {
+    let mut acc = Vec::new();
+    for line in raw.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        let (_, v) = all_consuming(&mut parser)(line)
+            .finish()
+            .map_err(|e| ParseError::from_nom(day, raw, e))?;
+        acc.push(v);
+    }
+    if acc.is_empty() {
+        return Err(ParseError::at(day, raw, 0, "at least one line"));
+    }
+    Ok(acc)
+}
+
+#[cfg(test)]
+mod test {
+    use nom::{character::complete::digit1, error::context};
+
+    use super::*;
+
+    #[test]
+    fn locates_bad_line() {
+        let raw = "12\n34\n  5x6\n78\n";
+        let err = parse_lines(0, raw, context("a number", digit1)).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.snippet, "  5x6");
+        assert_eq!(err.expected, "end of line");
+        assert_eq!(
+            err.to_string(),
+            "day 00: line 3, column 4: expected end of line\n  |\n3 |   5x6\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn reports_context() {
+        let raw = "12\r\nab\r\n";
+        let err = parse_lines(0, raw, context("a number", digit1)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.snippet, "ab");
+        assert_eq!(err.expected, "a number");
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        let err = parse_lines(0, " \n\n", context("a number", digit1)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+    }
+}